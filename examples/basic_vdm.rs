@@ -1,4 +1,5 @@
 use vdm::action::ActionType;
+use vdm::time::Seconds;
 use vdm::VDM;
 
 fn main() {
@@ -10,7 +11,7 @@ fn main() {
     // Since we used .props_mut() we can directly edit the Action without needing to set anything after.
     // Set is available if you want to completely replace an Action or its properties with .set_nth_props()
     props.name = "Skip 5 seconds in".to_string();
-    props.skip_to_time = Some(5.0);
+    props.skip_to = Seconds(5.0).into();
 
-    vdm.export("example.vdm");
+    vdm.export("example.vdm").unwrap();
 }
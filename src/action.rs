@@ -1,5 +1,52 @@
+use crate::colour::Rgba;
+use crate::time::{Seconds, Tick, TimePoint};
+use bitflags::bitflags;
 use regex::{CaptureMatches, Regex};
 use std::fmt::{self, Display, Formatter, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Identifies an action wherever it moves in its vdm, for as long as the program runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActionId(u64);
+
+impl Default for ActionId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionId {
+    /// A new ID that no other action has.
+    pub fn new() -> Self {
+        ActionId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+bitflags! {
+    /// The `FFADE_*` switches on a `ScreenFadeStart`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct FadeFlags: u8 {
+        const IN = 0x01;
+        const OUT = 0x02;
+        const MODULATE = 0x04;
+        const STAYOUT = 0x08;
+        const PURGE = 0x10;
+    }
+}
+
+impl FadeFlags {
+    /// The flag a vdm key like `FFADE_STAYOUT` switches.
+    pub fn from_key(key: &str) -> Option<Self> {
+        FadeFlags::from_name(key.strip_prefix("FFADE_")?)
+    }
+
+    /// The vdm keys of the flags that are set, in the order the game writes them.
+    pub fn keys(&self) -> impl Iterator<Item = String> {
+        self.iter_names().map(|(name, _)| format!("FFADE_{name}"))
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 pub enum TextEffect {
@@ -33,13 +80,14 @@ impl From<TextEffect> for String {
 
 #[derive(Debug, Clone)]
 pub struct Properties {
+    /// Kept when the properties are copied with `Action::props`, so `set_props` keeps the action's ID.
+    pub id: ActionId,
+    /// The key this action had in the file it was parsed from.
+    pub key: Option<String>,
     pub name: String,
-    pub start_tick: Option<i64>,
-    pub start_time: Option<f64>,
-    pub stop_tick: Option<i64>,
-    pub stop_time: Option<f64>,
-    pub skip_to_tick: Option<i64>,
-    pub skip_to_time: Option<f64>,
+    pub start: TimePoint,
+    pub stop: TimePoint,
+    pub skip_to: TimePoint,
     pub track: i64,
     pub spline: bool,
     pub stayout: bool,
@@ -57,14 +105,10 @@ pub struct Properties {
     pub font: String,
     pub commands: String,
     pub effect: TextEffect,
-    pub fade_in_enabled: bool,
-    pub fade_out_enabled: bool,
-    pub modulate_enabled: bool,
-    pub stay_out_enabled: bool,
-    pub purge_enabled: bool,
+    pub fade_flags: FadeFlags,
     pub xy: [f64; 2],
-    pub rgba1: [u8; 4],
-    pub rgba2: [u8; 4],
+    pub rgba1: Rgba,
+    pub rgba2: Rgba,
 }
 
 impl Default for Properties {
@@ -76,13 +120,12 @@ impl Default for Properties {
 impl Properties {
     pub fn new() -> Self {
         Properties {
+            id: ActionId::new(),
+            key: None,
             name: "Unnamed".to_string(),
-            start_tick: None,
-            start_time: None,
-            stop_tick: None,
-            stop_time: None,
-            skip_to_tick: None,
-            skip_to_time: None,
+            start: TimePoint::Unset,
+            stop: TimePoint::Unset,
+            skip_to: TimePoint::Unset,
             track: 0,
             spline: false,
             stayout: false,
@@ -99,97 +142,246 @@ impl Properties {
             fade_out: 0.0,
             fx_time: 0.0,
             effect: TextEffect::FadeInOut,
-            fade_in_enabled: false,
-            fade_out_enabled: false,
-            modulate_enabled: false,
-            stay_out_enabled: false,
-            purge_enabled: false,
+            fade_flags: FadeFlags::empty(),
             xy: [0.0, 0.0],
-            rgba1: [0, 0, 0, 0],
-            rgba2: [0, 0, 0, 0],
+            rgba1: Rgba::default(),
+            rgba2: Rgba::default(),
             commands: "".to_string(),
         }
     }
 
-    pub fn shift_by_tick(&mut self, adjustment: i64) {
-        if adjustment == 0 {
-            return;
+    /// Sets a property from its vdm key, unknown keys are ignored.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), &'static str> {
+        if let Some(flag) = FadeFlags::from_key(key) {
+            self.fade_flags.set(flag, value != "0");
+            return Ok(());
         }
 
-        if self.start_tick.is_some() {
-            self.start_tick = Some(self.start_tick.unwrap() + adjustment);
-
-            if self.start_tick.unwrap() < 0 {
-                self.start_tick = None;
+        //* readability is for nerds
+        match key {
+            "name" => {
+                self.name = value.to_string();
             }
-        } else if adjustment > 0 {
-            self.start_tick = Some(adjustment)
-        }
-
-        if self.start_time.is_some() {
-            self.start_time = Some(self.start_time.unwrap() + (adjustment as f64 / 66.0));
-
-            if self.start_time.unwrap() < 0.0 {
-                self.start_time = None;
+            "starttime" => {
+                self.start = parse_time(value)?;
+            }
+            "starttick" => {
+                self.start = parse_tick(value)?;
+            }
+            "skiptotick" => {
+                self.skip_to = parse_tick(value)?;
+            }
+            "skiptotime" => {
+                self.skip_to = parse_time(value)?;
+            }
+            "stoptick" => {
+                self.stop = parse_tick(value)?;
+            }
+            "stoptime" => {
+                self.stop = parse_time(value)?;
+            }
+            "track" => {
+                self.track = value.parse::<i64>().map_err(|_| "Invalid number")?;
+            }
+            "spline" => {
+                self.spline = value == "1";
+            }
+            "stayout" => {
+                self.stayout = value == "1";
+            }
+            "finalfov" => {
+                self.final_fov = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "playbackrate" => {
+                self.playback_rate = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "fovrateout" => {
+                self.fade_out = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "fovratein" => {
+                self.fade_in = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "fovhold" => {
+                self.hold_time = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "zoomintime" => {
+                self.zoom_in_time = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "zoomouttime" => {
+                self.zoom_out_time = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "duration" => {
+                self.duration = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "pausetime" => {
+                self.duration = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "holdtime" => {
+                self.hold_time = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "fadein" => {
+                self.fade_in = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "fadeout" => {
+                self.fade_out = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "fxtime" => {
+                self.fx_time = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "sound" => {
+                self.sound = value.to_string();
+            }
+            "message" => {
+                self.message = value.to_string();
+            }
+            "font" => {
+                self.font = value.to_string();
+            }
+            "commands" => {
+                self.commands = value.to_string();
             }
+            "x" => {
+                self.xy[0] = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "y" => {
+                self.xy[1] = value.parse::<f64>().map_err(|_| "Invalid number")?;
+            }
+            "r" | "r1" => {
+                self.rgba1.r = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "g" | "g1" => {
+                self.rgba1.g = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "b" | "b1" => {
+                self.rgba1.b = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "a" | "a1" => {
+                self.rgba1.a = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "r2" => {
+                self.rgba2.r = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "g2" => {
+                self.rgba2.g = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "b2" => {
+                self.rgba2.b = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "a2" => {
+                self.rgba2.a = value.parse::<u8>().map_err(|_| "Invalid number")?;
+            }
+            "FLICKER" if value != "0" => {
+                self.effect = TextEffect::Flicker;
+            }
+            "FADEINOUT" if value != "0" => {
+                self.effect = TextEffect::FadeInOut;
+            }
+            "WRITEOUT" if value != "0" => {
+                self.effect = TextEffect::WriteOut;
+            }
+            _ => {}
         }
 
-        if self.skip_to_tick.is_some() {
-            self.skip_to_tick = Some(self.skip_to_tick.unwrap() + adjustment);
-        }
+        Ok(())
+    }
 
-        if self.skip_to_time.is_some() {
-            self.skip_to_time = Some(self.skip_to_time.unwrap() + (adjustment as f64 / 66.0));
-        }
+    /// Reads a property by its vdm key, `None` for unset or unknown keys.
+    pub fn get(&self, key: &str) -> Option<String> {
+        let flag = |enabled: bool| enabled.then(|| "1".to_string());
 
-        if self.skip_to_time.is_some() && self.skip_to_time.unwrap() < 0.0 {
-            self.skip_to_time = None;
+        if let Some(fade_flag) = FadeFlags::from_key(key) {
+            return flag(self.fade_flags.contains(fade_flag));
         }
 
-        if self.skip_to_tick.is_some() && self.skip_to_tick.unwrap() < 0 {
-            self.skip_to_tick = None;
+        match key {
+            "name" => Some(self.name.clone()),
+            "starttick" | "stoptick" | "skiptotick" => match self.time_point(key) {
+                TimePoint::Tick(tick) => Some(tick.to_string()),
+                _ => None,
+            },
+            "starttime" | "stoptime" | "skiptotime" => match self.time_point(key) {
+                TimePoint::Time(time) => Some(time.to_string()),
+                _ => None,
+            },
+            "track" => Some(self.track.to_string()),
+            "spline" => Some(if self.spline { "1" } else { "0" }.to_string()),
+            "stayout" => Some(if self.stayout { "1" } else { "0" }.to_string()),
+            "finalfov" => Some(self.final_fov.to_string()),
+            "playbackrate" => Some(self.playback_rate.to_string()),
+            "fovrateout" | "fadeout" => Some(self.fade_out.to_string()),
+            "fovratein" | "fadein" => Some(self.fade_in.to_string()),
+            "fovhold" | "holdtime" => Some(self.hold_time.to_string()),
+            "zoomintime" => Some(self.zoom_in_time.to_string()),
+            "zoomouttime" => Some(self.zoom_out_time.to_string()),
+            "duration" | "pausetime" => Some(self.duration.to_string()),
+            "fxtime" => Some(self.fx_time.to_string()),
+            "sound" => Some(self.sound.clone()),
+            "message" => Some(self.message.clone()),
+            "font" => Some(self.font.clone()),
+            "commands" => Some(self.commands.clone()),
+            "x" => Some(self.xy[0].to_string()),
+            "y" => Some(self.xy[1].to_string()),
+            "r" | "r1" => Some(self.rgba1.r.to_string()),
+            "g" | "g1" => Some(self.rgba1.g.to_string()),
+            "b" | "b1" => Some(self.rgba1.b.to_string()),
+            "a" | "a1" => Some(self.rgba1.a.to_string()),
+            "r2" => Some(self.rgba2.r.to_string()),
+            "g2" => Some(self.rgba2.g.to_string()),
+            "b2" => Some(self.rgba2.b.to_string()),
+            "a2" => Some(self.rgba2.a.to_string()),
+            "FLICKER" => flag(matches!(self.effect, TextEffect::Flicker)),
+            "FADEINOUT" => flag(matches!(self.effect, TextEffect::FadeInOut)),
+            "WRITEOUT" => flag(matches!(self.effect, TextEffect::WriteOut)),
+            _ => None,
         }
     }
 
-    pub fn shift_by_time(&mut self, adjustment: f64) {
-        if adjustment == 0.0 {
-            return;
+    /// The start, stop or skip-to point a `*tick` or `*time` key belongs to.
+    fn time_point(&self, key: &str) -> TimePoint {
+        match key {
+            "starttick" | "starttime" => self.start,
+            "stoptick" | "stoptime" => self.stop,
+            "skiptotick" | "skiptotime" => self.skip_to,
+            _ => TimePoint::Unset,
         }
+    }
 
-        if self.start_tick.is_some() {
-            self.start_tick = Some(self.start_tick.unwrap() + (adjustment * 66.0) as i64);
-
-            if self.start_tick.unwrap() < 0 {
-                self.start_tick = None;
-            }
+    pub fn shift_by_tick(&mut self, adjustment: Tick, tick_rate: f64) {
+        if adjustment.0 == 0 {
+            return;
         }
 
-        if self.start_time.is_some() {
-            self.start_time = Some(self.start_time.unwrap() + adjustment);
-
-            if self.start_time.unwrap() < 0.0 {
-                self.start_time = None;
-            }
-        } else if adjustment > 0.0 {
-            self.start_time = Some(adjustment)
-        }
+        self.start = match self.start {
+            // Unset starts play from the beginning of the demo.
+            TimePoint::Unset if adjustment.0 > 0 => TimePoint::Tick(adjustment),
+            start => start.shift_ticks(adjustment, tick_rate),
+        };
+        self.stop = self.stop.shift_ticks(adjustment, tick_rate);
+        self.skip_to = self.skip_to.shift_ticks(adjustment, tick_rate);
+    }
 
-        if self.skip_to_tick.is_some() {
-            self.skip_to_tick = Some(self.start_tick.unwrap() + (adjustment * 66.0) as i64);
+    pub fn shift_by_time(&mut self, adjustment: Seconds, tick_rate: f64) {
+        if adjustment.0 == 0.0 {
+            return;
         }
 
-        if self.skip_to_time.is_some() {
-            self.skip_to_time = Some(self.skip_to_time.unwrap() + adjustment);
-        }
+        self.start = match self.start {
+            TimePoint::Unset if adjustment.0 > 0.0 => TimePoint::Time(adjustment),
+            start => start.shift_seconds(adjustment, tick_rate),
+        };
+        self.stop = self.stop.shift_seconds(adjustment, tick_rate);
+        self.skip_to = self.skip_to.shift_seconds(adjustment, tick_rate);
+    }
+}
 
-        if self.skip_to_time.unwrap() < 0.0 {
-            self.skip_to_time = None;
-        }
+fn parse_tick(value: &str) -> Result<TimePoint, &'static str> {
+    let tick = value.parse::<i64>().map_err(|_| "Invalid number")?;
+    Ok(TimePoint::Tick(Tick(tick)))
+}
 
-        if self.skip_to_tick.unwrap() < 0 {
-            self.skip_to_tick = None;
-        }
-    }
+fn parse_time(value: &str) -> Result<TimePoint, &'static str> {
+    let time = value.parse::<f64>().map_err(|_| "Invalid number")?;
+    Ok(TimePoint::Time(Seconds(time)))
 }
 
 impl From<CaptureMatches<'_, '_>> for Properties {
@@ -197,164 +389,14 @@ impl From<CaptureMatches<'_, '_>> for Properties {
         let mut property = Properties::new();
 
         for prop in factory_properties {
-            //* readability is for nerds
-            match prop[1].trim() {
-                "name" => {
-                    property.name = prop[2].to_string();
-                }
-                "starttime" => {
-                    property.start_time = Some(prop[2].parse::<f64>().unwrap());
-                }
-                "starttick" => {
-                    property.start_tick = Some(prop[2].parse::<i64>().unwrap());
-                }
-                "skiptotick" => {
-                    property.skip_to_tick = Some(prop[2].parse::<i64>().unwrap());
-                }
-                "skiptotime" => {
-                    property.skip_to_time = Some(prop[2].parse::<f64>().unwrap());
-                }
-                "stoptick" => {
-                    property.stop_tick = Some(prop[2].parse::<i64>().unwrap());
-                }
-                "stoptime" => {
-                    property.stop_time = Some(prop[2].parse::<f64>().unwrap());
-                }
-                "track" => {
-                    property.track = prop[2].parse::<i64>().unwrap();
-                }
-                "spline" => {
-                    property.spline = &prop[2] == "1";
-                }
-                "stayout" => {
-                    property.stayout = &prop[2] == "1";
-                }
-                "finalfov" => {
-                    property.final_fov = prop[2].parse::<f64>().unwrap();
-                }
-                "playbackrate" => {
-                    property.playback_rate = prop[2].parse::<f64>().unwrap();
-                }
-                "fovrateout" => {
-                    property.fade_out = prop[2].parse::<f64>().unwrap();
-                }
-                "fovratein" => {
-                    property.fade_in = prop[2].parse::<f64>().unwrap();
-                }
-                "fovhold" => {
-                    property.hold_time = prop[2].parse::<f64>().unwrap();
-                }
-                "zoomintime" => {
-                    property.zoom_in_time = prop[2].parse::<f64>().unwrap();
-                }
-                "zoomouttime" => {
-                    property.zoom_out_time = prop[2].parse::<f64>().unwrap();
-                }
-                "duration" => {
-                    property.duration = prop[2].parse::<f64>().unwrap();
-                }
-                "pausetime" => {
-                    property.duration = prop[2].parse::<f64>().unwrap();
-                }
-                "holdtime" => {
-                    property.hold_time = prop[2].parse::<f64>().unwrap();
-                }
-                "fadein" => {
-                    property.fade_in = prop[2].parse::<f64>().unwrap();
-                }
-                "fadeout" => {
-                    property.fade_out = prop[2].parse::<f64>().unwrap();
-                }
-                "fxtime" => {
-                    property.fx_time = prop[2].parse::<f64>().unwrap();
-                }
-                "sound" => {
-                    property.sound = prop[2].to_string();
-                }
-                "message" => {
-                    property.message = prop[2].to_string();
-                }
-                "font" => {
-                    property.font = prop[2].to_string();
-                }
-                "commands" => {
-                    property.commands = prop[2].to_string();
-                }
-                "x" => {
-                    property.xy[0] = prop[2].parse::<f64>().unwrap();
-                }
-                "y" => {
-                    property.xy[1] = prop[2].parse::<f64>().unwrap();
-                }
-                "r" => {
-                    property.rgba1[0] = prop[2].parse::<u8>().unwrap();
-                }
-                "r1" => {
-                    property.rgba1[0] = prop[2].parse::<u8>().unwrap();
-                }
-                "g" => {
-                    property.rgba1[1] = prop[2].parse::<u8>().unwrap();
-                }
-                "g1" => {
-                    property.rgba1[1] = prop[2].parse::<u8>().unwrap();
-                }
-                "b" => {
-                    property.rgba1[2] = prop[2].parse::<u8>().unwrap();
-                }
-                "b1" => {
-                    property.rgba1[2] = prop[2].parse::<u8>().unwrap();
-                }
-                "a" => {
-                    property.rgba1[3] = prop[2].parse::<u8>().unwrap();
-                }
-                "a1" => {
-                    property.rgba1[3] = prop[2].parse::<u8>().unwrap();
-                }
-                "r2" => {
-                    property.rgba2[0] = prop[2].parse::<u8>().unwrap();
-                }
-                "g2" => {
-                    property.rgba2[1] = prop[2].parse::<u8>().unwrap();
-                }
-                "b2" => {
-                    property.rgba2[2] = prop[2].parse::<u8>().unwrap();
-                }
-                "a2" => {
-                    property.rgba2[3] = prop[2].parse::<u8>().unwrap();
-                }
-                "FFADE_IN" => {
-                    property.fade_in_enabled = true;
-                }
-                "FFADE_OUT" => {
-                    property.fade_out_enabled = true;
-                }
-                "FFADE_MODULATE" => {
-                    property.modulate_enabled = true;
-                }
-                "FFADE_STAYOUT" => {
-                    property.stay_out_enabled = true;
-                }
-                "FFADE_PURGE" => {
-                    property.purge_enabled = true;
-                }
-                "FLICKER" => {
-                    property.effect = TextEffect::Flicker;
-                }
-                "FADEINOUT" => {
-                    property.effect = TextEffect::FadeInOut;
-                }
-                "WRITEOUT" => {
-                    property.effect = TextEffect::WriteOut;
-                }
-                _ => {}
-            }
+            property.set(prop[1].trim(), &prop[2]).unwrap();
         }
 
         property
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActionType {
     SkipAhead,
     StopPlayback,
@@ -368,6 +410,149 @@ pub enum ActionType {
     ZoomFov,
 }
 
+const START_KEYS: [&str; 3] = ["name", "starttick", "starttime"];
+
+/// Keys that set the same property.
+const KEY_ALIASES: [&[&str]; 8] = [
+    &["duration", "pausetime"],
+    &["fadeout", "fovrateout"],
+    &["fadein", "fovratein"],
+    &["holdtime", "fovhold"],
+    &["r", "r1"],
+    &["g", "g1"],
+    &["b", "b1"],
+    &["a", "a1"],
+];
+
+impl ActionType {
+    pub const ALL: [ActionType; 10] = [
+        ActionType::SkipAhead,
+        ActionType::StopPlayback,
+        ActionType::PlayCommands,
+        ActionType::ScreenFadeStart,
+        ActionType::TextMessageStart,
+        ActionType::PlayCDTrackStart,
+        ActionType::PlaySoundStart,
+        ActionType::Pause,
+        ActionType::ChangePlaybackRate,
+        ActionType::ZoomFov,
+    ];
+
+    /// The name written after `factory` in a vdm.
+    pub fn factory(&self) -> &'static str {
+        match self {
+            ActionType::SkipAhead => "SkipAhead",
+            ActionType::StopPlayback => "StopPlayback",
+            ActionType::PlayCommands => "PlayCommands",
+            ActionType::ScreenFadeStart => "ScreenFadeStart",
+            ActionType::TextMessageStart => "TextMessageStart",
+            ActionType::PlayCDTrackStart => "PlayCDTrackStart",
+            ActionType::PlaySoundStart => "PlaySoundStart",
+            ActionType::Pause => "Pause",
+            ActionType::ChangePlaybackRate => "ChangePlaybackRate",
+            ActionType::ZoomFov => "Zoom FOV",
+        }
+    }
+
+    pub fn from_factory(factory: &str) -> Option<ActionType> {
+        ActionType::ALL
+            .into_iter()
+            .find(|action_type| action_type.factory() == factory)
+    }
+
+    /// Whether `key` sets one of this factory's keys, including the other names
+    /// `Properties::set` accepts for them.
+    pub fn has_key(&self, key: &str) -> bool {
+        let keys = self.keys();
+
+        keys.contains(&key)
+            || KEY_ALIASES
+                .iter()
+                .find(|names| names.contains(&key))
+                .is_some_and(|names| names.iter().any(|name| keys.contains(name)))
+    }
+
+    /// Every key this factory reads, besides `factory` itself.
+    pub fn keys(&self) -> &'static [&'static str] {
+        match self {
+            ActionType::SkipAhead => {
+                &["name", "starttick", "starttime", "skiptotick", "skiptotime"]
+            }
+            ActionType::StopPlayback => &START_KEYS,
+            ActionType::PlayCommands => &["name", "starttick", "starttime", "commands"],
+            ActionType::ScreenFadeStart => &[
+                "name",
+                "starttick",
+                "starttime",
+                "duration",
+                "holdtime",
+                "FFADE_IN",
+                "FFADE_OUT",
+                "FFADE_MODULATE",
+                "FFADE_STAYOUT",
+                "FFADE_PURGE",
+                "r",
+                "g",
+                "b",
+                "a",
+            ],
+            ActionType::TextMessageStart => &[
+                "name",
+                "starttick",
+                "starttime",
+                "message",
+                "font",
+                "fadein",
+                "fadeout",
+                "holdtime",
+                "fxtime",
+                "FLICKER",
+                "FADEINOUT",
+                "WRITEOUT",
+                "x",
+                "y",
+                "r1",
+                "g1",
+                "b1",
+                "a1",
+                "r2",
+                "g2",
+                "b2",
+                "a2",
+            ],
+            ActionType::PlayCDTrackStart => &["name", "starttick", "starttime", "track"],
+            ActionType::PlaySoundStart => &["name", "starttick", "starttime", "sound"],
+            ActionType::Pause => &[
+                "name",
+                "starttick",
+                "starttime",
+                "stoptick",
+                "stoptime",
+                "pausetime",
+            ],
+            ActionType::ChangePlaybackRate => &[
+                "name",
+                "starttick",
+                "starttime",
+                "stoptick",
+                "stoptime",
+                "playbackrate",
+            ],
+            ActionType::ZoomFov => &[
+                "name",
+                "starttick",
+                "starttime",
+                "spline",
+                "stayout",
+                "finalfov",
+                "fovrateout",
+                "fovratein",
+                "fovhold",
+            ],
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Action {
     SkipAhead(Properties),
@@ -401,22 +586,57 @@ impl Action {
         }
     }
 
+    pub fn action_type(&self) -> ActionType {
+        match self {
+            Action::SkipAhead(_) => ActionType::SkipAhead,
+            Action::StopPlayback(_) => ActionType::StopPlayback,
+            Action::PlayCommands(_) => ActionType::PlayCommands,
+            Action::ScreenFadeStart(_) => ActionType::ScreenFadeStart,
+            Action::TextMessageStart(_) => ActionType::TextMessageStart,
+            Action::PlayCDTrackStart(_) => ActionType::PlayCDTrackStart,
+            Action::PlaySoundStart(_) => ActionType::PlaySoundStart,
+            Action::Pause(_) => ActionType::Pause,
+            Action::ChangePlaybackRate(_) => ActionType::ChangePlaybackRate,
+            Action::ZoomFov(_) => ActionType::ZoomFov,
+        }
+    }
+
+    pub fn id(&self) -> ActionId {
+        self.props_ref().id
+    }
+
+    /// A copy of this action with a new ID and no original key.
+    pub fn duplicate(&self) -> Self {
+        let mut action = self.clone();
+        let props = action.props_mut();
+        props.id = ActionId::new();
+        props.key = None;
+        action
+    }
+
     pub fn props(&self) -> Properties {
+        self.props_ref().clone()
+    }
+
+    fn props_ref(&self) -> &Properties {
         match self {
-            Action::SkipAhead(props) => props.clone(),
-            Action::StopPlayback(props) => props.clone(),
-            Action::PlayCommands(props) => props.clone(),
-            Action::ScreenFadeStart(props) => props.clone(),
-            Action::TextMessageStart(props) => props.clone(),
-            Action::PlayCDTrackStart(props) => props.clone(),
-            Action::PlaySoundStart(props) => props.clone(),
-            Action::Pause(props) => props.clone(),
-            Action::ChangePlaybackRate(props) => props.clone(),
-            Action::ZoomFov(props) => props.clone(),
+            Action::SkipAhead(props) => props,
+            Action::StopPlayback(props) => props,
+            Action::PlayCommands(props) => props,
+            Action::ScreenFadeStart(props) => props,
+            Action::TextMessageStart(props) => props,
+            Action::PlayCDTrackStart(props) => props,
+            Action::PlaySoundStart(props) => props,
+            Action::Pause(props) => props,
+            Action::ChangePlaybackRate(props) => props,
+            Action::ZoomFov(props) => props,
         }
     }
 
-    pub fn set_props(&mut self, new_props: Properties) -> Self {
+    /// This action with new properties, keeping its ID whatever `new_props` has.
+    pub fn set_props(&mut self, mut new_props: Properties) -> Self {
+        new_props.id = self.id();
+
         match self {
             Action::SkipAhead(_) => Action::SkipAhead(new_props),
             Action::StopPlayback(_) => Action::StopPlayback(new_props),
@@ -482,20 +702,16 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n",)?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.skip_to_tick.as_ref() {
-                    write!(f, "\t\tskiptotick \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
-                if let Some(prop) = props.skip_to_time.as_ref() {
-                    write!(f, "\t\tskiptotime \"{prop:.3}\"\r\n")?;
+                match props.skip_to {
+                    TimePoint::Tick(tick) => write!(f, "\t\tskiptotick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tskiptotime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
             }
             Action::StopPlayback(props) => {
@@ -503,12 +719,10 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
             }
             Action::PlayCommands(props) => {
@@ -516,12 +730,10 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n");
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
                 write!(f, "\t\tcommands \"{}\"\r\n", props.commands)?;
@@ -531,54 +743,34 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
                 write!(f, "\t\tduration \"{:.3}\"\r\n", props.duration)?;
 
                 write!(f, "\t\tholdtime \"{:.3}\"\r\n", props.hold_time)?;
 
-                if props.fade_in_enabled {
-                    write!(f, "\t\tFFADE_IN \"1\"\r\n")?;
+                for key in props.fade_flags.keys() {
+                    write!(f, "\t\t{key} \"1\"\r\n")?;
                 }
 
-                if props.fade_out_enabled {
-                    write!(f, "\t\tFFADE_OUT \"1\"\r\n")?;
-                }
-
-                if props.modulate_enabled {
-                    write!(f, "\t\tFFADE_MODULATE \"1\"\r\n")?;
-                }
-
-                if props.stay_out_enabled {
-                    write!(f, "\t\tFFADE_STAYOUT \"1\"\r\n")?;
-                }
-
-                if props.purge_enabled {
-                    write!(f, "\t\tFFADE_PURGE \"1\"\r\n")?;
-                }
-
-                write!(f, "\t\tr \"{}\"\r\n", props.rgba1[0])?;
-                write!(f, "\t\tg \"{}\"\r\n", props.rgba1[1])?;
-                write!(f, "\t\tb \"{}\"\r\n", props.rgba1[2])?;
-                write!(f, "\t\ta \"{}\"\r\n", props.rgba1[3])?;
+                write!(f, "\t\tr \"{}\"\r\n", props.rgba1.r)?;
+                write!(f, "\t\tg \"{}\"\r\n", props.rgba1.g)?;
+                write!(f, "\t\tb \"{}\"\r\n", props.rgba1.b)?;
+                write!(f, "\t\ta \"{}\"\r\n", props.rgba1.a)?;
             }
             Action::TextMessageStart(props) => {
                 write!(f, "\t\tfactory \"TextMessageStart\"\r\n")?;
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
                 write!(f, "\t\tmessage \"{}\"\r\n", props.message)?;
@@ -592,27 +784,25 @@ impl Display for Action {
                 write!(f, "\t\tx \"{}\"\r\n", props.xy[0])?;
                 write!(f, "\t\ty \"{}\"\r\n", props.xy[1])?;
 
-                write!(f, "\t\tr1 \"{}\"\r\n", props.rgba1[0])?;
-                write!(f, "\t\tg1 \"{}\"\r\n", props.rgba1[1])?;
-                write!(f, "\t\tb1 \"{}\"\r\n", props.rgba1[2])?;
-                write!(f, "\t\ta1 \"{}\"\r\n", props.rgba1[3])?;
+                write!(f, "\t\tr1 \"{}\"\r\n", props.rgba1.r)?;
+                write!(f, "\t\tg1 \"{}\"\r\n", props.rgba1.g)?;
+                write!(f, "\t\tb1 \"{}\"\r\n", props.rgba1.b)?;
+                write!(f, "\t\ta1 \"{}\"\r\n", props.rgba1.a)?;
 
-                write!(f, "\t\tr2 \"{}\"\r\n", props.rgba2[0])?;
-                write!(f, "\t\tg2 \"{}\"\r\n", props.rgba2[1])?;
-                write!(f, "\t\tb2 \"{}\"\r\n", props.rgba2[2])?;
-                write!(f, "\t\ta2 \"{}\"\r\n", props.rgba2[3])?;
+                write!(f, "\t\tr2 \"{}\"\r\n", props.rgba2.r)?;
+                write!(f, "\t\tg2 \"{}\"\r\n", props.rgba2.g)?;
+                write!(f, "\t\tb2 \"{}\"\r\n", props.rgba2.b)?;
+                write!(f, "\t\ta2 \"{}\"\r\n", props.rgba2.a)?;
             }
             Action::PlayCDTrackStart(props) => {
                 write!(f, "\t\tfactory \"PlayCDTrackStart\"\r\n")?;
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
                 write!(f, "\t\ttrack \"{}\"\r\n", props.track)?;
@@ -622,12 +812,10 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
                 write!(f, "\t\tsound \"{}\"\r\n", props.sound)?;
@@ -637,20 +825,16 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.stop_tick.as_ref() {
-                    write!(f, "\t\tstoptick \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
-                if let Some(prop) = props.stop_time.as_ref() {
-                    write!(f, "\t\tstoptime \"{prop}\"\r\n")?;
+                match props.stop {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstoptick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstoptime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
                 write!(f, "\t\tpausetime \"{:.6}\"\r\n", props.duration)?;
@@ -660,20 +844,16 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.stop_tick.as_ref() {
-                    write!(f, "\t\tstoptick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.stop_time.as_ref() {
-                    write!(f, "\t\tstoptime \"{prop}\"\r\n")?;
+                match props.stop {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstoptick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstoptime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
                 write!(f, "\t\tplaybackrate \"{:.6}\"\r\n", props.playback_rate)?;
@@ -683,16 +863,14 @@ impl Display for Action {
 
                 write!(f, "\t\tname \"{}\"\r\n", props.name)?;
 
-                if let Some(prop) = props.start_tick.as_ref() {
-                    write!(f, "\t\tstarttick \"{prop}\"\r\n")?;
-                }
-
-                if let Some(prop) = props.start_time.as_ref() {
-                    write!(f, "\t\tstarttime \"{prop}\"\r\n")?;
+                match props.start {
+                    TimePoint::Tick(tick) => write!(f, "\t\tstarttick \"{tick}\"\r\n")?,
+                    TimePoint::Time(time) => write!(f, "\t\tstarttime \"{time}\"\r\n")?,
+                    TimePoint::Unset => {}
                 }
 
-                write!(f, "\t\tspline \"{}\"\r\n", props.spline)?;
-                write!(f, "\t\tstayout \"{}\"\r\n", props.stayout)?;
+                write!(f, "\t\tspline \"{}\"\r\n", props.spline as u8)?;
+                write!(f, "\t\tstayout \"{}\"\r\n", props.stayout as u8)?;
                 write!(f, "\t\tfinalfov \"{:.6}\"\r\n", props.final_fov)?;
                 write!(f, "\t\tfovrateout \"{:.6}\"\r\n", props.fade_out)?;
                 write!(f, "\t\tfovratein \"{:.6}\"\r\n", props.fade_in)?;
@@ -0,0 +1,336 @@
+use crate::action::Action;
+use crate::sound;
+use crate::syntax::{tokenize, Token};
+use crate::time::Seconds;
+use crate::validate::{Issue, Severity};
+use crate::vpk::Vpk;
+use crate::VDM;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The fonts and sounds a game install has, for checking the ones a vdm uses.
+///
+/// Names are matched without case, like the engine does.
+#[derive(Debug, Clone, Default)]
+pub struct Assets {
+    /// Lowercase font name to the name as the scheme wrote it.
+    pub fonts: BTreeMap<String, String>,
+    /// Lowercase sound paths, relative to `sound/`.
+    pub sounds: BTreeMap<String, String>,
+    /// Where the sounds are, looked through in order.
+    pub sound_dirs: Vec<PathBuf>,
+    pub vpks: Vec<Vpk>,
+    /// Vpks found while scanning that couldn't be read, and why. The scan carries on without them.
+    pub unreadable: Vec<(PathBuf, &'static str)>,
+}
+
+/// Sound paths can start with these to change how they play.
+const SOUND_CHARS: &[char] = &[
+    '*', '?', '!', '#', '>', '@', '^', '<', ')', '(', '}', '$', '~', '&', '+',
+];
+
+impl Assets {
+    pub fn new() -> Self {
+        Assets::default()
+    }
+
+    /// Indexes a game directory like `.../Team Fortress 2/tf`, including its `custom` folder.
+    ///
+    /// Games that mount another game's files, like `hl2`, need that directory added too.
+    pub fn scan(game_dir: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let mut assets = Assets::new();
+        assets.add_game_dir(game_dir)?;
+        Ok(assets)
+    }
+
+    pub fn add_game_dir(&mut self, game_dir: impl AsRef<Path>) -> Result<(), &'static str> {
+        let game_dir = game_dir.as_ref();
+
+        if !game_dir.is_dir() {
+            return Err("Game directory not found");
+        }
+
+        let custom = fs::read_dir(game_dir.join("custom"))
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_else(|_| vec![]);
+
+        for dir in [game_dir.to_path_buf(), game_dir.join("download")]
+            .into_iter()
+            .chain(custom)
+        {
+            if dir.is_dir() {
+                self.add_loose_files(&dir);
+            } else if dir.extension().is_some_and(|ext| ext == "vpk") {
+                self.try_add_vpk(dir);
+            }
+        }
+
+        let entries = fs::read_dir(game_dir).map_err(|_| "Unable to read game directory")?;
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            if name.ends_with("_dir.vpk") {
+                self.try_add_vpk(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn try_add_vpk(&mut self, path: PathBuf) {
+        if let Err(error) = self.add_vpk(&path) {
+            self.unreadable.push((path, error));
+        }
+    }
+
+    /// The `resource/ClientScheme.res` and `sound/` folder of a game or custom folder.
+    fn add_loose_files(&mut self, dir: &Path) {
+        if let Some(scheme) = find_file(&dir.join("resource"), "clientscheme.res") {
+            self.add_scheme_file(&scheme, 0);
+        }
+
+        let sound_dir = dir.join("sound");
+        if sound_dir.is_dir() {
+            self.sound_dirs.push(sound_dir.clone());
+        }
+
+        let mut dirs = vec![sound_dir.clone()];
+
+        while let Some(next) = dirs.pop() {
+            let Ok(entries) = fs::read_dir(&next) else {
+                continue;
+            };
+
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if let Ok(relative) = path.strip_prefix(&sound_dir) {
+                    self.add_sound(&relative.to_string_lossy());
+                }
+            }
+        }
+    }
+
+    fn add_scheme_file(&mut self, path: &Path, depth: usize) {
+        let Ok(text) = fs::read_to_string(path) else {
+            return;
+        };
+
+        for base in self.add_scheme(&text) {
+            // Schemes can include each other, don't follow a loop forever.
+            if depth < 8 {
+                let base = path.with_file_name(base.replace('\\', "/"));
+                self.add_scheme_file(&base, depth + 1);
+            }
+        }
+    }
+
+    /// Reads the fonts out of a scheme file, returning the files it pulls in with `#base`.
+    pub fn add_scheme(&mut self, text: &str) -> Vec<String> {
+        let mut bases = vec![];
+        let mut blocks: Vec<String> = vec![];
+        let mut key: Option<String> = None;
+
+        for (token, _) in tokenize(text, &mut vec![], &mut vec![]) {
+            match (token, key.take()) {
+                (Token::Open, Some(name)) => {
+                    if blocks
+                        .last()
+                        .is_some_and(|parent| parent.eq_ignore_ascii_case("fonts"))
+                    {
+                        self.fonts.insert(name.to_lowercase(), name.clone());
+                    }
+                    blocks.push(name);
+                }
+                (Token::Open, None) => blocks.push(String::new()),
+                (Token::Close, _) => {
+                    blocks.pop();
+                }
+                // Platform conditions like `[$WIN32]` follow a value or block name.
+                (Token::Text(text), previous) if text.starts_with('[') && text.ends_with(']') => {
+                    key = previous;
+                }
+                (Token::Text(value), Some(name)) => {
+                    if name.eq_ignore_ascii_case("#base") && blocks.is_empty() {
+                        bases.push(value);
+                    }
+                }
+                (Token::Text(name), None) => key = Some(name),
+            }
+        }
+
+        bases
+    }
+
+    /// Adds the files under `sound/` listed in a vpk, and the fonts in its
+    /// `resource/ClientScheme.res`, which is where the stock scheme is.
+    pub fn add_vpk(&mut self, path: &Path) -> Result<(), &'static str> {
+        let vpk = Vpk::open(path)?;
+
+        for file in vpk.paths() {
+            if let Some(sound) = file.strip_prefix("sound/") {
+                self.add_sound(sound);
+            }
+        }
+
+        self.add_vpk_scheme(&vpk, "resource/clientscheme.res", 0);
+
+        self.vpks.push(vpk);
+        Ok(())
+    }
+
+    fn add_vpk_scheme(&mut self, vpk: &Vpk, path: &str, depth: usize) {
+        let Ok(data) = vpk.read(path) else {
+            return;
+        };
+
+        for base in self.add_scheme(&String::from_utf8_lossy(&data)) {
+            if depth < 8 {
+                let base = Path::new(path).with_file_name(base.replace('\\', "/"));
+                self.add_vpk_scheme(vpk, &base.to_string_lossy(), depth + 1);
+            }
+        }
+    }
+
+    pub fn add_sound(&mut self, path: &str) {
+        let path = path.replace('\\', "/");
+        self.sounds.insert(path.to_lowercase(), path);
+    }
+
+    pub fn has_font(&self, name: &str) -> bool {
+        self.fonts.contains_key(&name.to_lowercase())
+    }
+
+    pub fn has_sound(&self, sound: &str) -> bool {
+        self.sounds.contains_key(&sound_path(sound))
+    }
+
+    /// How long a sound plays for, read from the first loose file or vpk that has it.
+    pub fn sound_duration(&self, sound: &str) -> Result<Seconds, &'static str> {
+        let path = sound_path(sound);
+        let original = self.sounds.get(&path).ok_or("Unknown sound")?;
+
+        if let Some(file) = self
+            .sound_dirs
+            .iter()
+            .map(|dir| dir.join(original))
+            .find(|file| file.is_file())
+        {
+            return sound::open(file);
+        }
+
+        let path = format!("sound/{path}");
+        match self.vpks.iter().find(|vpk| vpk.contains(&path)) {
+            Some(vpk) => sound::in_vpk(vpk, &path),
+            None => Err("Unknown sound"),
+        }
+    }
+
+    /// The closest known font, if any is close enough to be a typo.
+    pub fn suggest_font(&self, name: &str) -> Option<&str> {
+        closest(&name.to_lowercase(), &self.fonts)
+    }
+
+    pub fn suggest_sound(&self, sound: &str) -> Option<&str> {
+        closest(&sound_path(sound), &self.sounds)
+    }
+}
+
+impl VDM {
+    /// Checks every font and sound the vdm uses is in `assets`.
+    pub fn check_assets(&self, assets: &Assets) -> Vec<Issue> {
+        let mut issues = vec![];
+
+        for (index, action) in self.actions.iter().enumerate() {
+            let (kind, name, suggestion) = match action {
+                Action::TextMessageStart(props)
+                    if !props.font.is_empty() && !assets.has_font(&props.font) =>
+                {
+                    ("font", &props.font, assets.suggest_font(&props.font))
+                }
+                // Sentences come from a script, not a file.
+                Action::PlaySoundStart(props)
+                    if !props.sound.is_empty()
+                        && !props.sound.starts_with('!')
+                        && !assets.has_sound(&props.sound) =>
+                {
+                    ("sound", &props.sound, assets.suggest_sound(&props.sound))
+                }
+                _ => continue,
+            };
+
+            let message = match suggestion {
+                Some(suggestion) => {
+                    format!("Unknown {kind} \"{name}\", did you mean \"{suggestion}\"?")
+                }
+                None => format!("Unknown {kind} \"{name}\""),
+            };
+
+            issues.push(Issue {
+                index,
+                severity: Severity::Warning,
+                message,
+            });
+        }
+
+        issues
+    }
+}
+
+/// A sound as it's looked up, without its sound chars or a leading `sound/`.
+fn sound_path(sound: &str) -> String {
+    let sound = sound
+        .trim_start_matches(SOUND_CHARS)
+        .replace('\\', "/")
+        .to_lowercase();
+
+    match sound.strip_prefix("sound/") {
+        Some(stripped) => stripped.to_string(),
+        None => sound,
+    }
+}
+
+fn closest<'a>(name: &str, known: &'a BTreeMap<String, String>) -> Option<&'a str> {
+    let limit = (name.chars().count() / 4).max(2);
+
+    known
+        .iter()
+        .map(|(lower, original)| (distance(name, lower), original))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, original)| original.as_str())
+}
+
+/// Levenshtein distance.
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let next = (diagonal + usize::from(a != *b))
+                .min(row[j] + 1)
+                .min(row[j + 1] + 1);
+            diagonal = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn find_file(dir: &Path, name: &str) -> Option<std::path::PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .is_some_and(|file| file.to_string_lossy().eq_ignore_ascii_case(name))
+        })
+}
@@ -0,0 +1,322 @@
+use crate::action::Action;
+use crate::syntax;
+use crate::time::Tick;
+use crate::validate::Severity;
+use crate::VDM;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// A demo and its vdm, either of which can be missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair {
+    pub demo: Option<PathBuf>,
+    pub vdm: Option<PathBuf>,
+}
+
+/// Built in operations for `Batch::apply`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    /// Reports every issue `VDM::validate` finds, without changing anything.
+    Validate,
+    /// Moves every action by a number of ticks.
+    Shift(Tick),
+    /// Writes each vdm back out as this crate would have written it, numbering actions from 1.
+    Regenerate,
+    /// Removes every `PlayCommands` action.
+    StripCommands,
+}
+
+/// What happened to one vdm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub written: bool,
+    pub notes: Vec<String>,
+    pub error: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub files: Vec<FileReport>,
+}
+
+impl Report {
+    pub fn failed(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter().filter(|file| file.error.is_some())
+    }
+
+    pub fn written(&self) -> usize {
+        self.files.iter().filter(|file| file.written).count()
+    }
+
+    pub fn print(&self) {
+        print!("{self}");
+    }
+}
+
+/// Every note and error, then a count of what was done.
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            for note in &file.notes {
+                writeln!(f, "{}: {note}", file.path.display())?;
+            }
+
+            if let Some(error) = file.error {
+                writeln!(f, "{}: error: {error}", file.path.display())?;
+            }
+        }
+
+        writeln!(
+            f,
+            "{} vdms, {} written, {} failed",
+            self.files.len(),
+            self.written(),
+            self.failed().count()
+        )
+    }
+}
+
+/// Every demo and vdm under a directory, for working on a whole archive at once.
+///
+/// With the `rayon` feature, files are worked on in parallel.
+#[derive(Debug, Clone)]
+pub struct Batch {
+    /// Sorted by path.
+    pub pairs: Vec<Pair>,
+    /// Directories under the one discovered that couldn't be read, and why. Each report lists
+    /// them as failed.
+    pub unreadable: Vec<(PathBuf, &'static str)>,
+    /// For shifting actions timed in seconds.
+    pub tick_rate: f64,
+}
+
+impl Batch {
+    /// Finds every `.dem` and `.vdm` under `dir`, pairing them by name.
+    ///
+    /// Only fails if `dir` itself can't be read, directories under it that can't be read are
+    /// kept in `unreadable`.
+    pub fn discover(dir: impl AsRef<Path>) -> Result<Batch, &'static str> {
+        let root = dir.as_ref();
+        let mut pairs: BTreeMap<PathBuf, Pair> = BTreeMap::new();
+        let mut unreadable = vec![];
+        let mut dirs = vec![root.to_path_buf()];
+
+        while let Some(dir) = dirs.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) if dir == root => return Err("Unable to read directory"),
+                Err(_) => {
+                    unreadable.push((dir, "Unable to read directory"));
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+
+                // Links aren't followed, so a link back up the tree can't loop.
+                if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                    dirs.push(path);
+                    continue;
+                }
+
+                let extension = path
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase());
+                let is_demo = match extension.as_deref() {
+                    Some("dem") => true,
+                    Some("vdm") => false,
+                    _ => continue,
+                };
+
+                let pair = pairs.entry(path.with_extension("")).or_insert(Pair {
+                    demo: None,
+                    vdm: None,
+                });
+
+                if is_demo {
+                    pair.demo = Some(path);
+                } else {
+                    pair.vdm = Some(path);
+                }
+            }
+        }
+
+        unreadable.sort();
+
+        Ok(Batch {
+            pairs: pairs.into_values().collect(),
+            unreadable,
+            tick_rate: 66.0,
+        })
+    }
+
+    /// Demos that have no vdm yet.
+    pub fn demos_without_vdm(&self) -> impl Iterator<Item = &Path> {
+        self.pairs
+            .iter()
+            .filter(|pair| pair.vdm.is_none())
+            .filter_map(|pair| pair.demo.as_deref())
+    }
+
+    /// Runs `f` on every vdm, writing back the ones it changes.
+    ///
+    /// `f` returns notes for the report, an error leaves the file as it was. Every file is
+    /// worked on even if some fail. Files that don't parse cleanly, or have keys or comments
+    /// saving would drop, fail rather than being written.
+    pub fn run<F>(&self, f: F) -> Report
+    where
+        F: Fn(&Pair, &mut VDM) -> Result<Vec<String>, &'static str> + Sync,
+    {
+        self.process(&f, false)
+    }
+
+    pub fn apply(&self, operation: Operation) -> Report {
+        let tick_rate = self.tick_rate;
+
+        match operation {
+            Operation::Validate => self.run(|_, vdm| {
+                Ok(vdm
+                    .validate(tick_rate)
+                    .iter()
+                    .map(|issue| {
+                        let severity = match issue.severity {
+                            Severity::Error => "error",
+                            Severity::Warning => "warning",
+                        };
+                        let name = vdm.nth(issue.index).props().name;
+                        format!("{severity} in \"{name}\": {}", issue.message)
+                    })
+                    .collect())
+            }),
+            Operation::Shift(ticks) => self.run(|_, vdm| {
+                for action in &mut vdm.actions {
+                    action.props_mut().shift_by_tick(ticks, tick_rate);
+                }
+                Ok(vec![])
+            }),
+            Operation::Regenerate => self.process(
+                &|_, vdm| {
+                    vdm.keep_keys = false;
+                    Ok(vec![])
+                },
+                true,
+            ),
+            Operation::StripCommands => self.run(|_, vdm| {
+                let before = vdm.len();
+                vdm.actions
+                    .retain(|action| !matches!(action, Action::PlayCommands(_)));
+
+                Ok(match before - vdm.len() {
+                    0 => vec![],
+                    removed => vec![format!("Removed {removed} PlayCommands")],
+                })
+            }),
+        }
+    }
+
+    fn process<F>(&self, f: &F, rewrite: bool) -> Report
+    where
+        F: Fn(&Pair, &mut VDM) -> Result<Vec<String>, &'static str> + Sync,
+    {
+        #[cfg(feature = "rayon")]
+        let pairs = self.pairs.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let pairs = self.pairs.iter();
+
+        let mut files = self
+            .unreadable
+            .iter()
+            .map(|(path, error)| FileReport {
+                path: path.clone(),
+                written: false,
+                notes: vec![],
+                error: Some(*error),
+            })
+            .collect::<Vec<_>>();
+
+        files.extend(
+            pairs
+                .filter(|pair| pair.vdm.is_some())
+                .map(|pair| process_pair(pair, f, rewrite))
+                .collect::<Vec<_>>(),
+        );
+
+        Report { files }
+    }
+}
+
+fn process_pair<F>(pair: &Pair, f: &F, rewrite: bool) -> FileReport
+where
+    F: Fn(&Pair, &mut VDM) -> Result<Vec<String>, &'static str>,
+{
+    let path = pair.vdm.clone().unwrap_or_default();
+    let mut report = FileReport {
+        path,
+        written: false,
+        notes: vec![],
+        error: None,
+    };
+
+    if let Err(error) = update(pair, f, rewrite, &mut report) {
+        report.error = Some(error);
+    }
+
+    report
+}
+
+fn update<F>(pair: &Pair, f: &F, rewrite: bool, report: &mut FileReport) -> Result<(), &'static str>
+where
+    F: Fn(&Pair, &mut VDM) -> Result<Vec<String>, &'static str>,
+{
+    let text = fs::read_to_string(&report.path).map_err(|_| "Error Opening File.")?;
+    let document = syntax::parse(&text);
+
+    let mut vdm = document.try_to_vdm()?;
+    vdm.name = report
+        .path
+        .file_stem()
+        .map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+    // Keep the file's own numbering unless told otherwise.
+    vdm.keep_keys = true;
+    let before = vdm.to_string();
+
+    report.notes = f(pair, &mut vdm)?;
+
+    // Rewriting compares against the file, so files already written this way are left alone.
+    let after = vdm.to_string();
+    let changed = match rewrite {
+        true => after != text,
+        false => after != before,
+    };
+
+    if !changed {
+        return Ok(());
+    }
+
+    // Only the keys each action's factory has are written.
+    let dropped = document.blocks.iter().any(|block| {
+        block
+            .entries
+            .iter()
+            .any(|entry| !block.is_used(entry) && !block.shadowed(entry))
+    });
+    if dropped {
+        return Err("Has keys that would be lost by saving");
+    }
+
+    if !document.comments.is_empty() {
+        return Err("Has comments that would be lost by saving");
+    }
+
+    fs::write(&report.path, after).map_err(|_| "Error Saving File.")?;
+    report.written = true;
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use crate::generator::{Clip, Generator};
+use crate::playback::Playback;
+use crate::ramp::SpeedRamp;
+use crate::time::{Seconds, Tick};
+use crate::VDM;
+use std::fmt::{self, Display, Formatter};
+use std::{fs, path::Path};
+
+/// Beat times in seconds into a song, sorted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Beats {
+    pub times: Vec<f64>,
+}
+
+impl Beats {
+    pub fn new(mut times: Vec<f64>) -> Self {
+        times.retain(|time| time.is_finite());
+        times.sort_by(f64::total_cmp);
+        Beats { times }
+    }
+
+    /// A steady beat from `offset` until `length`.
+    pub fn from_bpm(bpm: f64, offset: Seconds, length: Seconds) -> Self {
+        if bpm <= 0.0 {
+            return Beats::default();
+        }
+
+        let interval = 60.0 / bpm;
+        let count = match ((length.0 - offset.0) / interval).floor() {
+            beats if beats < 0.0 => 0,
+            beats => beats as usize + 1,
+        };
+
+        Beats::new(
+            (0..count)
+                .map(|beat| offset.0 + beat as f64 * interval)
+                .collect(),
+        )
+    }
+
+    /// Takes the first number on each line, so plain lists and most CSV exports both work.
+    /// Headers, comments and blank lines are skipped.
+    pub fn parse(text: &str) -> Self {
+        Beats::new(
+            text.lines()
+                .filter_map(|line| {
+                    line.split([',', ';', '\t', ' '])
+                        .map(str::trim)
+                        .find(|field| !field.is_empty())?
+                        .parse::<f64>()
+                        .ok()
+                })
+                .collect(),
+        )
+    }
+
+    pub fn open(file_path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let file_path = file_path.as_ref();
+
+        if file_path
+            .extension()
+            .is_some_and(|ext| ext == "txt" || ext == "csv")
+        {
+            let Ok(text) = fs::read_to_string(file_path) else {
+                return Err("Error Opening File.");
+            };
+
+            return Ok(Beats::parse(&text));
+        }
+
+        Err("Invalid file type")
+    }
+
+    /// The index of the beat closest to `time`, of the beats after `after`.
+    pub fn nearest(&self, time: f64, after: f64) -> Option<usize> {
+        self.times
+            .iter()
+            .enumerate()
+            .filter(|(_, beat)| **beat > after)
+            .min_by(|(_, a), (_, b)| (*a - time).abs().total_cmp(&(*b - time).abs()))
+            .map(|(index, _)| index)
+    }
+}
+
+/// A clip to cut to the music, with any speed ramps inside it.
+#[derive(Debug, Clone)]
+pub struct BeatClip {
+    pub clip: Clip,
+    pub ramps: Vec<SpeedRamp>,
+}
+
+impl BeatClip {
+    pub fn new(clip: Clip) -> Self {
+        BeatClip {
+            clip,
+            ramps: vec![],
+        }
+    }
+}
+
+/// Where a clip ended up in the edit, times are seconds into the song.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CutTiming {
+    pub name: String,
+    pub start_tick: i64,
+    pub stop_tick: i64,
+    /// Where the clip would have stopped without lining up to a beat.
+    pub original_stop_tick: i64,
+    pub start: f64,
+    pub end: f64,
+    /// The beat the clip ends on, `None` once the beats have run out.
+    pub end_beat: Option<usize>,
+    /// The beat each ramp starts on.
+    pub ramp_beats: Vec<Option<usize>>,
+}
+
+impl Display for CutTiming {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let beat = |beat: Option<usize>| beat.map_or("-".to_string(), |beat| beat.to_string());
+
+        write!(
+            f,
+            "{:>8.3} {:>8.3} {:>6} {:>8} {:>8} ({:+}) {}",
+            self.start,
+            self.end,
+            beat(self.end_beat),
+            self.start_tick,
+            self.stop_tick,
+            self.stop_tick - self.original_stop_tick,
+            self.name
+        )
+    }
+}
+
+/// The timings as a table, one clip per line.
+pub fn report(timings: &[CutTiming]) -> String {
+    let mut report = format!(
+        "{:>8} {:>8} {:>6} {:>8} {:>8} {}\n",
+        "start", "end", "beat", "tick", "stop", "clip"
+    );
+
+    for timing in timings {
+        report.push_str(&format!("{timing}\n"));
+    }
+
+    report
+}
+
+/// Cuts clips so each one starts and ends on a beat.
+///
+/// The edit starts on the first beat, and clips follow each other with no gaps. Lengths are
+/// taken from the playback simulation, so time spent slowed down by a ramp counts.
+#[derive(Debug, Clone)]
+pub struct BeatSync {
+    pub beats: Beats,
+    /// Compiles the lined up clips, and gives the tick rate.
+    pub generator: Generator,
+}
+
+impl BeatSync {
+    pub fn new(beats: Beats) -> Self {
+        BeatSync {
+            beats,
+            generator: Generator::new(),
+        }
+    }
+
+    /// Moves each ramp to start on the beat closest to it, then each clip's stop to the beat
+    /// closest to it.
+    pub fn align(&self, clips: &[BeatClip]) -> (Vec<BeatClip>, Vec<CutTiming>) {
+        let tick_rate = self.generator.tick_rate;
+        let Some(&first_beat) = self.beats.times.first() else {
+            return (clips.to_vec(), vec![]);
+        };
+
+        let mut aligned = vec![];
+        let mut timings = vec![];
+        let mut song = first_beat;
+
+        for clip in clips {
+            let mut clip = clip.clone();
+            let start_tick = clip.clip.start_tick;
+            let mut ramp_beats = vec![];
+
+            for i in 0..clip.ramps.len() {
+                // Only the ramps before this one change when it's reached.
+                let playback = Playback::new(&ramp_vdm(&clip.ramps[..i]), tick_rate);
+                let start = playback.time_at(start_tick).unwrap_or(0.0);
+                let offset = playback
+                    .time_at(clip.ramps[i].start.0)
+                    .map_or(0.0, |time| time - start);
+
+                // A ramp can start right as the clip does.
+                let beat = self.beats.nearest(song + offset, song - 1e-9);
+                ramp_beats.push(beat);
+
+                let Some(tick) =
+                    beat.and_then(|beat| playback.tick_at(start + self.beats.times[beat] - song))
+                else {
+                    continue;
+                };
+
+                let ramp = &mut clip.ramps[i];
+                let shift = Tick(tick) - ramp.start;
+                ramp.start = ramp.start + shift;
+                ramp.stop = ramp.stop + shift;
+            }
+
+            let playback = Playback::new(&ramp_vdm(&clip.ramps), tick_rate);
+            let original_stop_tick = clip.clip.stop_tick;
+            let length = playback
+                .duration(start_tick, original_stop_tick)
+                .unwrap_or(0.0);
+            let end_beat = self.beats.nearest(song + length, song);
+
+            let end = match end_beat {
+                Some(beat) => {
+                    let end = self.beats.times[beat];
+                    let start = playback.time_at(start_tick).unwrap_or(0.0);
+                    if let Some(tick) = playback.tick_at(start + end - song) {
+                        clip.clip.stop_tick = tick;
+                    }
+                    end
+                }
+                None => song + length,
+            };
+
+            timings.push(CutTiming {
+                name: clip.clip.name.clone(),
+                start_tick,
+                stop_tick: clip.clip.stop_tick,
+                original_stop_tick,
+                start: song,
+                end,
+                end_beat,
+                ramp_beats,
+            });
+
+            song = end;
+            aligned.push(clip);
+        }
+
+        (aligned, timings)
+    }
+
+    /// The vdm for the lined up clips, with their ramps, and where each cut lands.
+    pub fn compile(&self, clips: &[BeatClip]) -> (VDM, Vec<CutTiming>) {
+        let (aligned, timings) = self.align(clips);
+
+        let mut vdm = self.generator.compile(
+            &aligned
+                .iter()
+                .map(|clip| clip.clip.clone())
+                .collect::<Vec<_>>(),
+        );
+
+        for ramp in aligned.iter().flat_map(|clip| &clip.ramps) {
+            for action in ramp.actions() {
+                vdm.add(action);
+            }
+        }
+
+        (vdm, timings)
+    }
+}
+
+fn ramp_vdm(ramps: &[SpeedRamp]) -> VDM {
+    let mut vdm = VDM::new();
+
+    for action in ramps.iter().flat_map(SpeedRamp::actions) {
+        vdm.add(action);
+    }
+
+    vdm
+}
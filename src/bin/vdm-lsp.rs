@@ -0,0 +1,382 @@
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::{
+    CodeAction, CodeActionOrCommand, CodeActionParams, CompletionItem, CompletionItemKind,
+    CompletionOptions, CompletionParams, Diagnostic, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentFormattingParams, DocumentSymbol, DocumentSymbolParams, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, MarkupContent, MarkupKind, OneOf, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, SymbolKind, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+use std::{collections::HashMap, error::Error};
+use vdm::action::ActionType;
+use vdm::syntax::{self, Span};
+use vdm::validate::Severity;
+
+const TICK_RATE: f64 = 66.0;
+
+type Documents = HashMap<Url, String>;
+
+fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["\"".to_string()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(true.into()),
+        ..Default::default()
+    })?;
+    connection.initialize(capabilities)?;
+
+    let mut documents = Documents::new();
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+
+                let response = handle_request(&documents, request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = handle_notification(&mut documents, notification)? {
+                    let text = documents.get(&uri).map(String::as_str).unwrap_or_default();
+                    let params = PublishDiagnosticsParams {
+                        uri,
+                        diagnostics: diagnostics(text),
+                        version: None,
+                    };
+
+                    connection
+                        .sender
+                        .send(Message::Notification(Notification::new(
+                            "textDocument/publishDiagnostics".to_string(),
+                            params,
+                        )))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    // The writer thread only stops once the connection is gone.
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Keeps the open documents up to date, returns the document that needs new diagnostics.
+fn handle_notification(
+    documents: &mut Documents,
+    notification: Notification,
+) -> Result<Option<Url>, serde_json::Error> {
+    match notification.method.as_str() {
+        "textDocument/didOpen" => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            documents.insert(uri.clone(), params.text_document.text);
+            Ok(Some(uri))
+        }
+        "textDocument/didChange" => {
+            let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+
+            // Full sync, so the last change is the whole document.
+            if let Some(change) = params.content_changes.into_iter().last() {
+                documents.insert(uri.clone(), change.text);
+            }
+            Ok(Some(uri))
+        }
+        "textDocument/didClose" => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+            documents.remove(&params.text_document.uri);
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn handle_request(documents: &Documents, request: Request) -> Response {
+    let id = request.id.clone();
+
+    let result = match request.method.as_str() {
+        "textDocument/completion" => {
+            serde_json::from_value(request.params).map(|params: CompletionParams| {
+                let position = params.text_document_position;
+                let text = document(documents, &position.text_document.uri);
+                serde_json::to_value(completion(text, position.position))
+            })
+        }
+        "textDocument/hover" => {
+            serde_json::from_value(request.params).map(|params: HoverParams| {
+                let position = params.text_document_position_params;
+                let text = document(documents, &position.text_document.uri);
+                serde_json::to_value(hover(text, position.position))
+            })
+        }
+        "textDocument/documentSymbol" => {
+            serde_json::from_value(request.params).map(|params: DocumentSymbolParams| {
+                let text = document(documents, &params.text_document.uri);
+                serde_json::to_value(symbols(text))
+            })
+        }
+        "textDocument/formatting" => {
+            serde_json::from_value(request.params).map(|params: DocumentFormattingParams| {
+                let text = document(documents, &params.text_document.uri);
+                serde_json::to_value(format(text))
+            })
+        }
+        "textDocument/codeAction" => {
+            serde_json::from_value(request.params).map(|params: CodeActionParams| {
+                let uri = params.text_document.uri;
+                let text = document(documents, &uri);
+                serde_json::to_value(renumber(text, uri))
+            })
+        }
+        _ => {
+            return Response::new_err(
+                id,
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request {}", request.method),
+            )
+        }
+    };
+
+    match result {
+        Ok(Ok(value)) => Response::new_ok(id, value),
+        Ok(Err(error)) | Err(error) => Response::new_err(
+            id,
+            lsp_server::ErrorCode::InvalidParams as i32,
+            error.to_string(),
+        ),
+    }
+}
+
+fn document<'a>(documents: &'a Documents, uri: &Url) -> &'a str {
+    documents.get(uri).map(String::as_str).unwrap_or_default()
+}
+
+fn diagnostics(text: &str) -> Vec<Diagnostic> {
+    let lines = LineIndex::new(text);
+
+    syntax::parse(text)
+        .diagnostics(TICK_RATE)
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            range: lines.range(text, diagnostic.span),
+            severity: Some(match diagnostic.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+            }),
+            source: Some("vdm".to_string()),
+            message: diagnostic.message,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Factory names after `factory`, otherwise the keys the surrounding factory still needs.
+fn completion(text: &str, position: Position) -> Vec<CompletionItem> {
+    let lines = LineIndex::new(text);
+    let offset = lines.offset(text, position);
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let before = text[line_start..offset].trim_start();
+
+    if before.starts_with("factory") && before.len() > "factory".len() {
+        return ActionType::ALL
+            .iter()
+            .map(|action_type| CompletionItem {
+                label: action_type.factory().to_string(),
+                kind: Some(CompletionItemKind::CLASS),
+                ..Default::default()
+            })
+            .collect();
+    }
+
+    let document = syntax::parse(text);
+    let Some(block) = document
+        .blocks
+        .iter()
+        .find(|block| block.span.contains(offset))
+    else {
+        return vec![];
+    };
+    let Some(action_type) = block.action_type() else {
+        return vec![CompletionItem {
+            label: "factory".to_string(),
+            kind: Some(CompletionItemKind::PROPERTY),
+            ..Default::default()
+        }];
+    };
+
+    action_type
+        .keys()
+        .iter()
+        .filter(|key| block.entry(key).is_none())
+        .map(|key| CompletionItem {
+            label: key.to_string(),
+            kind: Some(CompletionItemKind::PROPERTY),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Shows tick values in seconds and time values in ticks.
+fn hover(text: &str, position: Position) -> Option<Hover> {
+    let lines = LineIndex::new(text);
+    let offset = lines.offset(text, position);
+    let document = syntax::parse(text);
+
+    let entry = document
+        .blocks
+        .iter()
+        .flat_map(|block| &block.entries)
+        .find(|entry| entry.value_span.contains(offset) || entry.key_span.contains(offset))?;
+
+    let value = match entry.key.as_str() {
+        "starttick" | "stoptick" | "skiptotick" => {
+            let ticks = entry.value.parse::<i64>().ok()?;
+            format!(
+                "{:.3} seconds at {TICK_RATE} tick",
+                ticks as f64 / TICK_RATE
+            )
+        }
+        "starttime" | "stoptime" | "skiptotime" => {
+            let seconds = entry.value.parse::<f64>().ok()?;
+            format!("tick {} at {TICK_RATE} tick", (seconds * TICK_RATE).round())
+        }
+        _ => return None,
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::PlainText,
+            value,
+        }),
+        range: Some(lines.range(text, entry.value_span)),
+    })
+}
+
+#[allow(deprecated)]
+fn symbols(text: &str) -> Vec<DocumentSymbol> {
+    let lines = LineIndex::new(text);
+
+    syntax::parse(text)
+        .blocks
+        .iter()
+        .map(|block| DocumentSymbol {
+            name: block
+                .entry("name")
+                .map_or_else(|| block.key.clone(), |entry| entry.value.clone()),
+            detail: block.entry("factory").map(|entry| entry.value.clone()),
+            kind: SymbolKind::EVENT,
+            tags: None,
+            deprecated: None,
+            range: lines.range(text, block.span),
+            selection_range: lines.range(text, block.key_span),
+            children: None,
+        })
+        .collect()
+}
+
+/// Rewrites the document with the crate's serializer, as long as nothing would be lost.
+fn format(text: &str) -> Vec<TextEdit> {
+    let document = syntax::parse(text);
+
+    if !document.errors.is_empty() || document.blocks.iter().any(|b| b.to_action().is_err()) {
+        return vec![];
+    }
+
+    let lines = LineIndex::new(text);
+    vec![TextEdit {
+        range: lines.range(
+            text,
+            Span {
+                start: 0,
+                end: text.len(),
+            },
+        ),
+        new_text: document.to_vdm().to_string(),
+    }]
+}
+
+/// Offers to renumber the action keys `"1"`, `"2"`, ... in order.
+fn renumber(text: &str, uri: Url) -> Vec<CodeActionOrCommand> {
+    let lines = LineIndex::new(text);
+
+    let edits = syntax::parse(text)
+        .blocks
+        .iter()
+        .enumerate()
+        .filter(|(i, block)| block.key != (i + 1).to_string())
+        .map(|(i, block)| TextEdit {
+            range: lines.range(text, block.key_span),
+            new_text: format!("\"{}\"", i + 1),
+        })
+        .collect::<Vec<_>>();
+
+    if edits.is_empty() {
+        return vec![];
+    }
+
+    vec![CodeActionOrCommand::CodeAction(CodeAction {
+        title: "Renumber actions".to_string(),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri, edits)])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })]
+}
+
+/// Converts between byte offsets and LSP positions, which count UTF-16 code units.
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(text: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { starts }
+    }
+
+    fn position(&self, text: &str, offset: usize) -> Position {
+        let line = self.starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.starts[line];
+        let character = text[start..offset].encode_utf16().count();
+
+        Position::new(line as u32, character as u32)
+    }
+
+    fn range(&self, text: &str, span: Span) -> Range {
+        Range::new(
+            self.position(text, span.start),
+            self.position(text, span.end),
+        )
+    }
+
+    fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(&start) = self.starts.get(position.line as usize) else {
+            return text.len();
+        };
+
+        let mut units = 0;
+        for (i, c) in text[start..].char_indices() {
+            if units >= position.character as usize || c == '\n' {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+
+        text.len()
+    }
+}
@@ -0,0 +1,518 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, Borders, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+};
+use ratatui::{DefaultTerminal, Frame};
+use std::{env, io, path::PathBuf};
+use vdm::action::{Action, ActionType};
+use vdm::history::History;
+use vdm::playback::{Playback, Segment};
+use vdm::time::TimePoint;
+use vdm::validate::{Issue, Severity};
+use vdm::VDM;
+
+const TICK_RATE: f64 = 66.0;
+
+#[derive(PartialEq, Eq)]
+enum Focus {
+    Actions,
+    Fields,
+}
+
+enum Mode {
+    Normal,
+    Editing(String),
+    Adding(ListState),
+}
+
+struct App {
+    history: History,
+    path: PathBuf,
+    selected: usize,
+    field: usize,
+    focus: Focus,
+    mode: Mode,
+    dirty: bool,
+    status: String,
+    quit: bool,
+}
+
+fn main() -> io::Result<()> {
+    let Some(path) = env::args().nth(1).map(PathBuf::from) else {
+        eprintln!("Usage: vdm-tui <file.vdm>");
+        return Ok(());
+    };
+
+    let vdm = if path.exists() {
+        match VDM::open(&path) {
+            Ok(vdm) => vdm,
+            Err(error) => {
+                eprintln!("{error}");
+                return Ok(());
+            }
+        }
+    } else {
+        VDM::new()
+    };
+
+    let mut app = App {
+        history: History::new(vdm),
+        path,
+        selected: 0,
+        field: 0,
+        focus: Focus::Actions,
+        mode: Mode::Normal,
+        dirty: false,
+        status: "? for help".to_string(),
+        quit: false,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                    self.dirty |= !self.history.take_changes().is_empty();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        if self.vdm().is_empty() {
+            return &[];
+        }
+
+        self.vdm().nth(self.selected).action_type().keys()
+    }
+
+    fn vdm(&self) -> &VDM {
+        self.history.vdm()
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        match &mut self.mode {
+            Mode::Editing(input) => match key.code {
+                KeyCode::Enter => {
+                    let input = input.clone();
+                    self.mode = Mode::Normal;
+                    self.commit_field(&input);
+                }
+                KeyCode::Esc => self.mode = Mode::Normal,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Mode::Adding(list) => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => list.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => list.select_next(),
+                KeyCode::Enter => {
+                    let action_type = ActionType::ALL[list.selected().unwrap_or(0)];
+                    self.mode = Mode::Normal;
+                    self.add(action_type);
+                }
+                KeyCode::Esc => self.mode = Mode::Normal,
+                _ => {}
+            },
+            Mode::Normal => self.handle_normal_key(key),
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Char('q') if self.dirty => {
+                self.dirty = false;
+                self.status = "Unsaved changes, press q again to quit".to_string();
+            }
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char('?') => {
+                self.status = "tab focus | a add | d duplicate | x delete | J/K move | enter edit | u undo | r redo | s save | q quit".to_string();
+            }
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Actions => Focus::Fields,
+                    Focus::Fields => Focus::Actions,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::Char('K') => self.reorder(-1),
+            KeyCode::Char('J') => self.reorder(1),
+            KeyCode::Char('a') => {
+                self.mode = Mode::Adding(ListState::default().with_selected(Some(0)));
+            }
+            KeyCode::Char('d') if !self.vdm().is_empty() => {
+                let action = self.vdm().nth(self.selected).duplicate();
+                self.history.insert(self.selected + 1, action);
+                self.selected += 1;
+            }
+            KeyCode::Char('x') | KeyCode::Delete if !self.vdm().is_empty() => {
+                self.history.remove(self.selected);
+                self.selected = self.selected.min(self.vdm().len().saturating_sub(1));
+            }
+            KeyCode::Char('u') | KeyCode::Char('z') if ctrl || key.code == KeyCode::Char('u') => {
+                self.history.undo();
+                self.clamp_selection();
+            }
+            KeyCode::Char('r') | KeyCode::Char('y') if ctrl || key.code == KeyCode::Char('r') => {
+                self.history.redo();
+                self.clamp_selection();
+            }
+            KeyCode::Enter if self.focus == Focus::Fields && !self.fields().is_empty() => {
+                let key = self.fields()[self.field];
+                let value = self.vdm().nth(self.selected).props().get(key);
+                self.mode = Mode::Editing(value.unwrap_or_default());
+            }
+            KeyCode::Char('s') => match self.vdm().export(&self.path) {
+                Ok(()) => {
+                    self.dirty = false;
+                    self.status = format!("Saved {}", self.path.display());
+                }
+                Err(error) => {
+                    self.status = format!("Unable to save {}: {error}", self.path.display());
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn move_cursor(&mut self, by: isize) {
+        match self.focus {
+            Focus::Actions => {
+                self.selected = self
+                    .selected
+                    .saturating_add_signed(by)
+                    .min(self.vdm().len().saturating_sub(1));
+                self.field = 0;
+            }
+            Focus::Fields => {
+                self.field = self
+                    .field
+                    .saturating_add_signed(by)
+                    .min(self.fields().len().saturating_sub(1));
+            }
+        }
+    }
+
+    fn reorder(&mut self, by: isize) {
+        let Some(to) = self.selected.checked_add_signed(by) else {
+            return;
+        };
+
+        if to >= self.vdm().len() {
+            return;
+        }
+
+        self.history.move_action(self.selected, to);
+        self.selected = to;
+    }
+
+    fn add(&mut self, action_type: ActionType) {
+        let at = if self.vdm().is_empty() {
+            0
+        } else {
+            self.selected + 1
+        };
+        self.history.insert(at, Action::new(action_type));
+        self.selected = at;
+        self.focus = Focus::Fields;
+        self.field = 0;
+    }
+
+    fn commit_field(&mut self, input: &str) {
+        let key = self.fields()[self.field];
+        let mut props = self.vdm().nth(self.selected).props();
+
+        // Clearing a tick or time unsets the point, as long as it is written in that unit.
+        let point = match key {
+            "starttick" | "starttime" => Some(&mut props.start),
+            "stoptick" | "stoptime" => Some(&mut props.stop),
+            "skiptotick" | "skiptotime" => Some(&mut props.skip_to),
+            _ => None,
+        };
+
+        if let Some(point) = point.filter(|_| input.is_empty()) {
+            let is_tick = key.ends_with("tick");
+
+            match *point {
+                TimePoint::Tick(_) if is_tick => *point = TimePoint::Unset,
+                TimePoint::Time(_) if !is_tick => *point = TimePoint::Unset,
+                _ => return,
+            }
+        } else if let Err(error) = props.set(key, input) {
+            self.status = format!("{key}: {error}");
+            return;
+        }
+
+        self.history.set_nth_props(self.selected, props);
+    }
+
+    fn clamp_selection(&mut self) {
+        self.selected = self.selected.min(self.vdm().len().saturating_sub(1));
+        self.field = self.field.min(self.fields().len().saturating_sub(1));
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let issues = self.vdm().validate(TICK_RATE);
+
+        let [main, timeline, problems, status] = Layout::vertical([
+            Constraint::Min(8),
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [actions, fields] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(main);
+
+        self.draw_actions(frame, actions, &issues);
+        self.draw_fields(frame, fields);
+        self.draw_timeline(frame, timeline);
+        self.draw_issues(frame, problems, &issues);
+
+        let dirty = if self.dirty { " [+]" } else { "" };
+        frame.render_widget(
+            Paragraph::new(format!("{}{dirty} | {}", self.path.display(), self.status)),
+            status,
+        );
+
+        match &self.mode {
+            Mode::Editing(input) => {
+                let area = popup(frame.area(), 60, 3);
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(format!("{input}_")).block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(format!(" {} ", self.fields()[self.field])),
+                    ),
+                    area,
+                );
+            }
+            Mode::Adding(list) => {
+                let area = popup(frame.area(), 30, ActionType::ALL.len() as u16 + 2);
+                let items = ActionType::ALL
+                    .iter()
+                    .map(|action_type| ListItem::new(action_type.factory()))
+                    .collect::<Vec<_>>();
+
+                frame.render_widget(Clear, area);
+                frame.render_stateful_widget(
+                    List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title(" Add action "))
+                        .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+                    area,
+                    &mut list.clone(),
+                );
+            }
+            Mode::Normal => {}
+        }
+    }
+
+    fn draw_actions(&self, frame: &mut Frame, area: Rect, issues: &[Issue]) {
+        let rows = self.vdm().actions.iter().enumerate().map(|(i, action)| {
+            let props = action.props();
+            let start = match props.start {
+                TimePoint::Tick(tick) => tick.to_string(),
+                TimePoint::Time(time) => format!("{time}s"),
+                TimePoint::Unset => String::new(),
+            };
+            let flag = if issues.iter().any(|issue| issue.index == i) {
+                "!"
+            } else {
+                ""
+            };
+
+            Row::new(vec![
+                (i + 1).to_string(),
+                flag.to_string(),
+                action.action_type().factory().to_string(),
+                props.name,
+                start,
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Length(1),
+                Constraint::Length(18),
+                Constraint::Min(10),
+                Constraint::Length(10),
+            ],
+        )
+        .header(
+            Row::new(vec!["#", "", "Factory", "Name", "Start"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(focused_block(" Actions ", self.focus == Focus::Actions))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut state =
+            TableState::default().with_selected((!self.vdm().is_empty()).then_some(self.selected));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn draw_fields(&self, frame: &mut Frame, area: Rect) {
+        let mut rows = vec![];
+
+        if !self.vdm().is_empty() {
+            let props = self.vdm().nth(self.selected).props();
+
+            for key in self.fields() {
+                rows.push(Row::new(vec![
+                    key.to_string(),
+                    props.get(key).unwrap_or_default(),
+                ]));
+            }
+        }
+
+        let table = Table::new(rows, [Constraint::Length(14), Constraint::Min(10)])
+            .block(focused_block(" Fields ", self.focus == Focus::Fields))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut state = TableState::default().with_selected(
+            (self.focus == Focus::Fields && !self.vdm().is_empty()).then_some(self.field),
+        );
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    /// Action markers on the first line, played and skipped ticks on the second.
+    fn draw_timeline(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Timeline ");
+        let width = block.inner(area).width.max(1) as usize;
+        let playback = Playback::new(self.vdm(), TICK_RATE);
+
+        let last_tick = playback
+            .fired
+            .iter()
+            .map(|fired| fired.tick)
+            .chain(playback.segments.iter().map(|segment| match *segment {
+                Segment::Play { stop_tick, .. } => stop_tick,
+                Segment::Pause { tick, .. } => tick,
+                Segment::Skip { to_tick, .. } => to_tick,
+            }))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let column =
+            |tick: i64| (tick.max(0) as usize * (width - 1) / last_tick as usize).min(width - 1);
+
+        let mut markers = vec![Span::raw(" "); width];
+        for fired in &playback.fired {
+            let style = if fired.index == self.selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let symbol = match self.vdm().nth(fired.index).action_type() {
+                ActionType::SkipAhead => ">",
+                ActionType::StopPlayback => "X",
+                ActionType::PlayCommands => "C",
+                ActionType::ScreenFadeStart => "F",
+                ActionType::TextMessageStart => "T",
+                ActionType::PlayCDTrackStart => "D",
+                ActionType::PlaySoundStart => "S",
+                ActionType::Pause => "P",
+                ActionType::ChangePlaybackRate => "R",
+                ActionType::ZoomFov => "Z",
+            };
+            markers[column(fired.tick)] = Span::styled(symbol, style);
+        }
+
+        let mut bar = vec![' '; width];
+        for segment in &playback.segments {
+            let (from, to, c) = match *segment {
+                Segment::Play {
+                    start_tick,
+                    stop_tick,
+                    ..
+                } => (start_tick, stop_tick, '='),
+                Segment::Skip {
+                    from_tick, to_tick, ..
+                } => (from_tick, to_tick, '.'),
+                Segment::Pause { tick, .. } => (tick, tick, '|'),
+            };
+            for cell in &mut bar[column(from)..=column(to)] {
+                *cell = c;
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(markers),
+                Line::from(bar.into_iter().collect::<String>()),
+            ])
+            .block(block),
+            area,
+        );
+    }
+
+    fn draw_issues(&self, frame: &mut Frame, area: Rect, issues: &[Issue]) {
+        let lines = issues
+            .iter()
+            .map(|issue| {
+                let color = match issue.severity {
+                    Severity::Error => Color::Red,
+                    Severity::Warning => Color::Yellow,
+                };
+                Line::styled(
+                    format!("{}: {}", issue.index + 1, issue.message),
+                    Style::default().fg(color),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Issues ")),
+            area,
+        );
+    }
+}
+
+fn focused_block(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(style)
+}
+
+fn popup(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
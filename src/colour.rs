@@ -0,0 +1,171 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A colour as the demo player takes it, each channel from 0 to 255.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// The CSS level 2 colour names, plus `transparent`.
+const NAMES: [(&str, Rgba); 18] = [
+    ("black", Rgba::rgb(0, 0, 0)),
+    ("silver", Rgba::rgb(192, 192, 192)),
+    ("gray", Rgba::rgb(128, 128, 128)),
+    ("white", Rgba::rgb(255, 255, 255)),
+    ("maroon", Rgba::rgb(128, 0, 0)),
+    ("red", Rgba::rgb(255, 0, 0)),
+    ("purple", Rgba::rgb(128, 0, 128)),
+    ("fuchsia", Rgba::rgb(255, 0, 255)),
+    ("green", Rgba::rgb(0, 128, 0)),
+    ("lime", Rgba::rgb(0, 255, 0)),
+    ("olive", Rgba::rgb(128, 128, 0)),
+    ("yellow", Rgba::rgb(255, 255, 0)),
+    ("navy", Rgba::rgb(0, 0, 128)),
+    ("blue", Rgba::rgb(0, 0, 255)),
+    ("teal", Rgba::rgb(0, 128, 128)),
+    ("aqua", Rgba::rgb(0, 255, 255)),
+    ("orange", Rgba::rgb(255, 165, 0)),
+    ("transparent", Rgba::new(0, 0, 0, 0)),
+];
+
+impl Rgba {
+    pub const BLACK: Rgba = Rgba::rgb(0, 0, 0);
+    pub const WHITE: Rgba = Rgba::rgb(255, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba { r, g, b, a }
+    }
+
+    /// Fully opaque.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Rgba::new(r, g, b, 255)
+    }
+
+    /// Reads `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, the `#` is optional.
+    pub fn from_hex(hex: &str) -> Result<Self, &'static str> {
+        let hex = hex.trim().trim_start_matches('#');
+
+        if !hex.is_ascii() {
+            return Err("Invalid hex colour");
+        }
+
+        let digit = |i: usize, width: usize| -> Result<u8, &'static str> {
+            let value = u8::from_str_radix(&hex[i * width..(i + 1) * width], 16)
+                .map_err(|_| "Invalid hex colour")?;
+            // A single digit stands for both, so `f` is `ff`.
+            Ok(if width == 1 { value * 17 } else { value })
+        };
+
+        match hex.len() {
+            3 => Ok(Rgba::rgb(digit(0, 1)?, digit(1, 1)?, digit(2, 1)?)),
+            4 => Ok(Rgba::new(
+                digit(0, 1)?,
+                digit(1, 1)?,
+                digit(2, 1)?,
+                digit(3, 1)?,
+            )),
+            6 => Ok(Rgba::rgb(digit(0, 2)?, digit(1, 2)?, digit(2, 2)?)),
+            8 => Ok(Rgba::new(
+                digit(0, 2)?,
+                digit(1, 2)?,
+                digit(2, 2)?,
+                digit(3, 2)?,
+            )),
+            _ => Err("Invalid hex colour"),
+        }
+    }
+
+    /// `#rrggbbaa`.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let name = if name == "grey" { "gray" } else { &name };
+
+        NAMES
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, colour)| *colour)
+    }
+
+    /// Reads `r, g, b` or `r, g, b, a`, optionally wrapped as `(...)`, `rgb(...)` or `rgba(...)`.
+    pub fn from_tuple(tuple: &str) -> Result<Self, &'static str> {
+        let inner = tuple
+            .trim()
+            .trim_start_matches("rgba")
+            .trim_start_matches("rgb")
+            .trim_start_matches('(')
+            .trim_end_matches(')');
+
+        let channels = inner
+            .split(',')
+            .map(|channel| channel.trim().parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "Invalid colour channel")?;
+
+        match channels[..] {
+            [r, g, b] => Ok(Rgba::rgb(r, g, b)),
+            [r, g, b, a] => Ok(Rgba::new(r, g, b, a)),
+            _ => Err("Expected 3 or 4 colour channels"),
+        }
+    }
+}
+
+/// Accepts anything `from_hex`, `from_name` or `from_tuple` does.
+impl FromStr for Rgba {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(colour) = Rgba::from_name(s) {
+            return Ok(colour);
+        }
+
+        if s.contains(',') {
+            return Rgba::from_tuple(s);
+        }
+
+        Rgba::from_hex(s)
+    }
+}
+
+impl Display for Rgba {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+impl From<[u8; 4]> for Rgba {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Rgba::new(r, g, b, a)
+    }
+}
+
+impl From<Rgba> for [u8; 4] {
+    fn from(colour: Rgba) -> Self {
+        [colour.r, colour.g, colour.b, colour.a]
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgba {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Rgba::rgb(r, g, b)
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for Rgba {
+    fn from((r, g, b, a): (u8, u8, u8, u8)) -> Self {
+        Rgba::new(r, g, b, a)
+    }
+}
+
+impl From<Rgba> for (u8, u8, u8, u8) {
+    fn from(colour: Rgba) -> Self {
+        (colour.r, colour.g, colour.b, colour.a)
+    }
+}
@@ -0,0 +1,812 @@
+use std::{fs, path::Path};
+
+const HEADER_LEN: usize = 1072;
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+/// The fixed size header at the start of every .dem file.
+#[derive(Debug, Clone)]
+pub struct DemoHeader {
+    pub demo_protocol: i32,
+    pub network_protocol: i32,
+    pub server_name: String,
+    pub client_name: String,
+    pub map_name: String,
+    pub game_directory: String,
+    pub playback_time: f32,
+    pub ticks: i32,
+    pub frames: i32,
+    pub signon_length: i32,
+}
+
+/// A player as seen in the `userinfo` string table.
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub name: String,
+    pub user_id: i32,
+    /// The SteamID as written by the server, usually `[U:1:...]`.
+    pub steam_id: String,
+    pub account_id: u32,
+    pub fake_player: bool,
+    pub hltv: bool,
+    /// The demo tick this entry was read at.
+    pub tick: i64,
+}
+
+impl Player {
+    pub fn steam_id64(&self) -> u64 {
+        STEAM_ID64_BASE + self.account_id as u64
+    }
+
+    /// Checks a SteamID in any of the `[U:1:x]`, `STEAM_0:y:z` or SteamID64 formats against this player.
+    pub fn matches(&self, steam_id: &str) -> bool {
+        !self.fake_player && account_id(steam_id).is_some_and(|id| id == self.account_id)
+    }
+}
+
+/// Every player entry read from a demo, in the order they were read.
+#[derive(Debug, Clone, Default)]
+pub struct Roster {
+    pub players: Vec<Player>,
+}
+
+impl Roster {
+    /// Finds the most recent entry for a SteamID at a given tick.
+    ///
+    /// Entries read after the tick are only used when there is nothing earlier.
+    pub fn find(&self, steam_id: &str, tick: i64) -> Option<&Player> {
+        let mut matches = self
+            .players
+            .iter()
+            .filter(|player| player.matches(steam_id));
+        let first = matches.clone().next();
+
+        matches
+            .filter(|player| player.tick <= tick)
+            .max_by_key(|player| player.tick)
+            .or(first)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Demo {
+    pub header: DemoHeader,
+    pub roster: Roster,
+    /// The server tick at demo tick 0, read from the first `net_Tick` message.
+    pub server_tick_offset: Option<i64>,
+    pub name: String,
+    /// Packets that stopped at a message this couldn't read, by tick, with the reason.
+    pub unread_packets: Vec<(i64, &'static str)>,
+}
+
+impl Demo {
+    pub fn open(file_path: impl AsRef<Path>) -> Result<Demo, &'static str> {
+        let file_path = file_path.as_ref();
+
+        if file_path.extension().is_some_and(|ext| ext == "dem") {
+            let Ok(file) = fs::read(file_path) else {
+                return Err("Error Opening File.");
+            };
+
+            let mut demo = Demo::from_bytes(&file)?;
+            demo.name = file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_owned();
+
+            return Ok(demo);
+        }
+
+        Err("Invalid file type")
+    }
+
+    /// Reads the header and the player roster.
+    ///
+    /// The roster comes from the string table snapshots stored in the demo and the
+    /// `userinfo` updates in its packets, so players joining late and name changes are seen.
+    /// A packet with a message this can't read is left there, keeping the players found so far,
+    /// and is listed in `unread_packets`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Demo, &'static str> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != b"HL2DEMO\0" {
+            return Err("Invalid demo header");
+        }
+
+        let header = DemoHeader {
+            demo_protocol: read_i32(bytes, 8),
+            network_protocol: read_i32(bytes, 12),
+            server_name: read_cstr(&bytes[16..276]),
+            client_name: read_cstr(&bytes[276..536]),
+            map_name: read_cstr(&bytes[536..796]),
+            game_directory: read_cstr(&bytes[796..1056]),
+            playback_time: f32::from_le_bytes(bytes[1056..1060].try_into().unwrap()),
+            ticks: read_i32(bytes, 1060),
+            frames: read_i32(bytes, 1064),
+            signon_length: read_i32(bytes, 1068),
+        };
+
+        let mut roster = Roster::default();
+        let mut server_tick_offset = None;
+        let mut tables = vec![];
+        let mut unread_packets = vec![];
+
+        for frame in Frames::new(&bytes[HEADER_LEN..]) {
+            let frame = frame?;
+
+            match frame.command {
+                FRAME_STRING_TABLES => {
+                    read_user_info(frame.data, frame.tick, &mut roster.players)?;
+                }
+                FRAME_SIGNON | FRAME_PACKET => {
+                    if frame.command == FRAME_PACKET && server_tick_offset.is_none() {
+                        server_tick_offset =
+                            read_server_tick(frame.data).map(|tick| tick - frame.tick);
+                    }
+
+                    if let Err(error) =
+                        read_packet(frame.data, frame.tick, &mut tables, &mut roster.players)
+                    {
+                        unread_packets.push((frame.tick, error));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Demo {
+            header,
+            roster,
+            server_tick_offset,
+            name: String::new(),
+            unread_packets,
+        })
+    }
+}
+
+/// Converts a SteamID in any common format to its 32 bit account id.
+pub fn account_id(steam_id: &str) -> Option<u32> {
+    let steam_id = steam_id.trim();
+
+    if let Some(id) = steam_id
+        .strip_prefix("[U:1:")
+        .and_then(|s| s.strip_suffix(']'))
+    {
+        return id.parse().ok();
+    }
+
+    if let Some(id) = steam_id.strip_prefix("STEAM_") {
+        let mut parts = id.split(':').skip(1);
+        let y = parts.next()?.parse::<u32>().ok()?;
+        let z = parts.next()?.parse::<u32>().ok()?;
+        return Some(z * 2 + y);
+    }
+
+    let id = steam_id.parse::<u64>().ok()?;
+    id.checked_sub(STEAM_ID64_BASE)
+        .and_then(|id| u32::try_from(id).ok())
+}
+
+const FRAME_SIGNON: u8 = 1;
+const FRAME_PACKET: u8 = 2;
+const FRAME_SYNC_TICK: u8 = 3;
+const FRAME_CONSOLE_CMD: u8 = 4;
+const FRAME_USER_CMD: u8 = 5;
+const FRAME_DATA_TABLES: u8 = 6;
+const FRAME_STOP: u8 = 7;
+const FRAME_STRING_TABLES: u8 = 8;
+
+const CMD_INFO_LEN: usize = 76;
+
+pub(crate) struct Frame<'a> {
+    pub command: u8,
+    pub tick: i64,
+    pub data: &'a [u8],
+}
+
+pub(crate) struct Frames<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    done: bool,
+}
+
+impl<'a> Frames<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Frames {
+            bytes,
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        let Some(slice) = self.bytes.get(self.pos..self.pos + len) else {
+            return Err("Unexpected end of demo");
+        };
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn take_i32(&mut self) -> Result<i32, &'static str> {
+        Ok(read_i32(self.take(4)?, 0))
+    }
+
+    fn take_sized(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = self.take_i32()?;
+        let len = usize::try_from(len).map_err(|_| "Invalid frame length")?;
+        self.take(len)
+    }
+
+    fn next_frame(&mut self) -> Result<Option<Frame<'a>>, &'static str> {
+        // Some recorders end the file without a stop frame.
+        if self.pos >= self.bytes.len() {
+            return Ok(None);
+        }
+
+        let command = self.take(1)?[0];
+        let tick = self.take_i32()? as i64;
+
+        let data = match command {
+            FRAME_SIGNON | FRAME_PACKET => {
+                // cmd info followed by the in and out sequence numbers
+                self.take(CMD_INFO_LEN + 8)?;
+                self.take_sized()?
+            }
+            FRAME_SYNC_TICK => &[],
+            FRAME_CONSOLE_CMD | FRAME_DATA_TABLES | FRAME_STRING_TABLES => self.take_sized()?,
+            FRAME_USER_CMD => {
+                self.take(4)?;
+                self.take_sized()?
+            }
+            FRAME_STOP => return Ok(None),
+            _ => return Err("Unknown demo frame"),
+        };
+
+        Ok(Some(Frame {
+            command,
+            tick,
+            data,
+        }))
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<Frame<'a>, &'static str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let frame = self.next_frame().transpose();
+        if !matches!(frame, Some(Ok(_))) {
+            self.done = true;
+        }
+
+        frame
+    }
+}
+
+/// Reads bits least significant first, the way the engine writes them.
+pub(crate) struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, pos: 0 }
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, &'static str> {
+        let Some(byte) = self.bytes.get(self.pos / 8) else {
+            return Err("Unexpected end of data");
+        };
+        let bit = byte >> (self.pos % 8) & 1 == 1;
+        self.pos += 1;
+        Ok(bit)
+    }
+
+    pub fn read_bits(&mut self, count: u32) -> Result<u32, &'static str> {
+        let mut value = 0;
+        for i in 0..count {
+            if self.read_bit()? {
+                value |= 1 << i;
+            }
+        }
+        Ok(value)
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>, &'static str> {
+        (0..len).map(|_| Ok(self.read_bits(8)? as u8)).collect()
+    }
+
+    pub fn remaining(&self) -> usize {
+        (self.bytes.len() * 8).saturating_sub(self.pos)
+    }
+
+    pub fn skip(&mut self, count: usize) -> Result<(), &'static str> {
+        if count > self.remaining() {
+            return Err("Unexpected end of data");
+        }
+        self.pos += count;
+        Ok(())
+    }
+
+    /// Reads `count` bits into bytes of their own, the last byte padded with zeros.
+    pub fn read_bit_vec(&mut self, count: usize) -> Result<Vec<u8>, &'static str> {
+        if count > self.remaining() {
+            return Err("Unexpected end of data");
+        }
+
+        let mut bytes = self.read_bytes(count / 8)?;
+        if !count.is_multiple_of(8) {
+            bytes.push(self.read_bits((count % 8) as u32)? as u8);
+        }
+        Ok(bytes)
+    }
+
+    /// A protobuf style varint, 7 bits to a byte.
+    pub fn read_var_int(&mut self) -> Result<u32, &'static str> {
+        let mut value = 0;
+        for i in 0..5 {
+            let byte = self.read_bits(8)?;
+            value |= (byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid varint")
+    }
+
+    pub fn read_string(&mut self) -> Result<String, &'static str> {
+        let mut bytes = vec![];
+        loop {
+            let byte = self.read_bits(8)? as u8;
+            if byte == 0 {
+                break;
+            }
+            bytes.push(byte);
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+const NET_MSG_TYPE_BITS: u32 = 6;
+const NET_TICK: u32 = 3;
+const SVC_CREATE_STRING_TABLE: u32 = 12;
+const SVC_UPDATE_STRING_TABLE: u32 = 13;
+
+/// Longest user data a string table entry can have, as a bit count of its length in bytes.
+const MAX_USERDATA_BITS: u32 = 14;
+/// How many earlier keys an entry key can start with.
+const KEY_HISTORY: usize = 32;
+
+/// Reads the server tick from a packet that starts with a `net_Tick` message.
+fn read_server_tick(data: &[u8]) -> Option<i64> {
+    let mut reader = BitReader::new(data);
+
+    if reader.read_bits(NET_MSG_TYPE_BITS).ok()? != NET_TICK {
+        return None;
+    }
+
+    reader.read_bits(32).ok().map(|tick| tick as i32 as i64)
+}
+
+/// A string table created by the server, enough to read updates to it.
+struct StringTable {
+    name: String,
+    max_entries: u32,
+    /// The size of every entry's data in bits, when they're all the same.
+    fixed_size: Option<u32>,
+}
+
+fn log2(value: u32) -> u32 {
+    value.checked_ilog2().unwrap_or(0)
+}
+
+fn skip_sized(reader: &mut BitReader, length_bits: u32) -> Result<(), &'static str> {
+    let length = reader.read_bits(length_bits)? as usize;
+    reader.skip(length)
+}
+
+fn skip_coord(reader: &mut BitReader) -> Result<(), &'static str> {
+    let int = reader.read_bit()?;
+    let fraction = reader.read_bit()?;
+    if int || fraction {
+        reader.skip(1 + if int { 14 } else { 0 } + if fraction { 5 } else { 0 })?;
+    }
+    Ok(())
+}
+
+/// Reads the messages in a packet, keeping track of the string tables and adding players
+/// from `userinfo` entries.
+///
+/// Messages that say how long they are are skipped over, others are read only far enough
+/// to get past them.
+fn read_packet(
+    data: &[u8],
+    tick: i64,
+    tables: &mut Vec<StringTable>,
+    players: &mut Vec<Player>,
+) -> Result<(), &'static str> {
+    let mut reader = BitReader::new(data);
+
+    // Anything shorter than a message type is padding to the end of the byte.
+    while reader.remaining() >= NET_MSG_TYPE_BITS as usize {
+        match reader.read_bits(NET_MSG_TYPE_BITS)? {
+            // Nop
+            0 => {}
+            // Disconnect, StringCmd and Print
+            1 | 4 | 7 => {
+                reader.read_string()?;
+            }
+            // File
+            2 => {
+                reader.skip(32)?;
+                reader.read_string()?;
+                reader.skip(1)?;
+            }
+            NET_TICK => reader.skip(64)?,
+            // SetConVar
+            5 => {
+                for _ in 0..reader.read_bits(8)? {
+                    reader.read_string()?;
+                    reader.read_string()?;
+                }
+            }
+            // SignonState
+            6 => reader.skip(40)?,
+            // ServerInfo
+            8 => {
+                let version = reader.read_bits(16)?;
+                let map_hash = if version >= 18 { 128 } else { 32 };
+                reader.skip(32 + 1 + 1 + 32 + 16 + map_hash + 8 + 8 + 32 + 8)?;
+                for _ in 0..4 {
+                    reader.read_string()?;
+                }
+                if version >= 16 {
+                    reader.skip(1)?;
+                }
+            }
+            // SendTable
+            9 => {
+                reader.skip(1)?;
+                skip_sized(&mut reader, 16)?;
+            }
+            // ClassInfo
+            10 => {
+                let count = reader.read_bits(16)?;
+                if !reader.read_bit()? {
+                    for _ in 0..count {
+                        reader.skip(log2(count) as usize + 1)?;
+                        reader.read_string()?;
+                        reader.read_string()?;
+                    }
+                }
+            }
+            // SetPause
+            11 => reader.skip(1)?,
+            SVC_CREATE_STRING_TABLE => {
+                let name = reader.read_string()?;
+                let max_entries = reader.read_bits(16)?;
+                let entries = reader.read_bits(log2(max_entries) + 1)?;
+                let length = reader.read_var_int()? as usize;
+                let fixed_size = match reader.read_bit()? {
+                    true => {
+                        reader.skip(12)?;
+                        Some(reader.read_bits(4)?)
+                    }
+                    false => None,
+                };
+                let compressed = reader.read_bit()?;
+                let data = reader.read_bit_vec(length)?;
+
+                let table = StringTable {
+                    name,
+                    max_entries,
+                    fixed_size,
+                };
+
+                if table.name == "userinfo" {
+                    let data = match compressed {
+                        true => decompress_table(&data)?,
+                        false => data,
+                    };
+                    read_entries(&mut BitReader::new(&data), &table, entries, tick, players)?;
+                }
+
+                tables.push(table);
+            }
+            SVC_UPDATE_STRING_TABLE => {
+                let id = reader.read_bits(5)? as usize;
+                let entries = match reader.read_bit()? {
+                    true => reader.read_bits(16)?,
+                    false => 1,
+                };
+                let length = reader.read_bits(20)? as usize;
+                let data = reader.read_bit_vec(length)?;
+
+                if let Some(table) = tables.get(id).filter(|table| table.name == "userinfo") {
+                    read_entries(&mut BitReader::new(&data), table, entries, tick, players)?;
+                }
+            }
+            // VoiceInit
+            14 => {
+                reader.read_string()?;
+                if reader.read_bits(8)? == 255 {
+                    reader.skip(16)?;
+                }
+            }
+            // VoiceData
+            15 => {
+                reader.skip(16)?;
+                skip_sized(&mut reader, 16)?;
+            }
+            // Sounds
+            17 => {
+                let reliable = reader.read_bit()?;
+                if !reliable {
+                    reader.skip(8)?;
+                }
+                skip_sized(&mut reader, if reliable { 8 } else { 16 })?;
+            }
+            // SetView
+            18 => reader.skip(11)?,
+            // FixAngle
+            19 => reader.skip(1 + 48)?,
+            // CrosshairAngle
+            20 => reader.skip(48)?,
+            // BspDecal
+            21 => {
+                let axes = [reader.read_bit()?, reader.read_bit()?, reader.read_bit()?];
+                for _ in axes.iter().filter(|has| **has) {
+                    skip_coord(&mut reader)?;
+                }
+                reader.skip(9)?;
+                if reader.read_bit()? {
+                    reader.skip(11 + 12)?;
+                }
+                reader.skip(1)?;
+            }
+            // UserMessage
+            23 => {
+                reader.skip(8)?;
+                skip_sized(&mut reader, 11)?;
+            }
+            // EntityMessage
+            24 => {
+                reader.skip(11 + 9)?;
+                skip_sized(&mut reader, 11)?;
+            }
+            // GameEvent
+            25 => skip_sized(&mut reader, 11)?,
+            // PacketEntities
+            26 => {
+                reader.skip(11)?;
+                if reader.read_bit()? {
+                    reader.skip(32)?;
+                }
+                reader.skip(1 + 11)?;
+                let length = reader.read_bits(20)? as usize;
+                reader.skip(1 + length)?;
+            }
+            // TempEntities
+            27 => {
+                reader.skip(8)?;
+                let length = reader.read_var_int()? as usize;
+                reader.skip(length)?;
+            }
+            // Prefetch
+            28 => reader.skip(14)?,
+            // Menu
+            29 => {
+                reader.skip(16)?;
+                let length = reader.read_bits(16)? as usize;
+                reader.skip(length * 8)?;
+            }
+            // GameEventList
+            30 => {
+                reader.skip(9)?;
+                skip_sized(&mut reader, 20)?;
+            }
+            // GetCvarValue
+            31 => {
+                reader.skip(32)?;
+                reader.read_string()?;
+            }
+            // CmdKeyValues
+            32 => {
+                let length = reader.read_bits(32)? as usize;
+                reader.skip(length * 8)?;
+            }
+            _ => return Err("Unknown packet message"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads string table entries, adding a player for each `userinfo` entry with data.
+fn read_entries(
+    reader: &mut BitReader,
+    table: &StringTable,
+    count: u32,
+    tick: i64,
+    players: &mut Vec<Player>,
+) -> Result<(), &'static str> {
+    let entry_bits = log2(table.max_entries);
+    let mut index = -1i64;
+    let mut history: Vec<String> = vec![];
+
+    for _ in 0..count {
+        index = match reader.read_bit()? {
+            true => index + 1,
+            false => reader.read_bits(entry_bits)? as i64,
+        };
+
+        if reader.read_bit()? {
+            // Keys can start with part of an earlier one.
+            let key = match reader.read_bit()? {
+                true => {
+                    let earlier = reader.read_bits(5)? as usize;
+                    let length = reader.read_bits(5)? as usize;
+                    let earlier = history.get(earlier).map_or("", |key| key.as_str());
+                    earlier.get(..length).unwrap_or(earlier).to_string() + &reader.read_string()?
+                }
+                false => reader.read_string()?,
+            };
+
+            history.push(key);
+            if history.len() > KEY_HISTORY {
+                history.remove(0);
+            }
+        }
+
+        if reader.read_bit()? {
+            let user_data = match table.fixed_size {
+                Some(bits) => reader.read_bit_vec(bits as usize)?,
+                None => {
+                    let len = reader.read_bits(MAX_USERDATA_BITS)? as usize;
+                    reader.read_bytes(len)?
+                }
+            };
+
+            if let Some(player) = parse_player_info(&user_data, tick) {
+                players.push(player);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Compressed tables start with both their sizes, then `SNAP` and the snappy data.
+fn decompress_table(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    match data.get(8..12) {
+        Some(b"SNAP") => snappy(&data[12..]),
+        _ => Err("Unsupported string table compression"),
+    }
+}
+
+/// Decompresses a raw snappy block.
+fn snappy(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    const INVALID: &str = "Invalid snappy data";
+
+    let mut reader = BitReader::new(data);
+    let length = reader.read_var_int()? as usize;
+    let mut at = data.len() - reader.remaining() / 8;
+    let mut out: Vec<u8> = Vec::with_capacity(length);
+
+    let mut take = |count: usize| -> Result<usize, &'static str> {
+        let bytes = data.get(at..at + count).ok_or(INVALID)?;
+        at += count;
+        Ok(bytes
+            .iter()
+            .rev()
+            .fold(0, |value, &byte| value << 8 | byte as usize))
+    };
+
+    while let Ok(tag) = take(1) {
+        let (length, offset) = match tag & 0b11 {
+            0 => {
+                let length = match tag >> 2 {
+                    // Longer literals give their length in the next 1 to 4 bytes.
+                    short @ 0..60 => short,
+                    long => take(long - 59)?,
+                } + 1;
+
+                for _ in 0..length {
+                    out.push(take(1)? as u8);
+                }
+                continue;
+            }
+            1 => (((tag >> 2) & 0b111) + 4, (tag >> 5) << 8 | take(1)?),
+            2 => ((tag >> 2) + 1, take(2)?),
+            _ => ((tag >> 2) + 1, take(4)?),
+        };
+
+        if offset == 0 || offset > out.len() {
+            return Err(INVALID);
+        }
+
+        // Copies can overlap what they're copying.
+        for _ in 0..length {
+            out.push(out[out.len() - offset]);
+        }
+    }
+
+    if out.len() != length {
+        return Err(INVALID);
+    }
+
+    Ok(out)
+}
+
+fn read_user_info(data: &[u8], tick: i64, players: &mut Vec<Player>) -> Result<(), &'static str> {
+    let mut reader = BitReader::new(data);
+    let table_count = reader.read_bits(8)?;
+
+    for _ in 0..table_count {
+        let table_name = reader.read_string()?;
+        let is_user_info = table_name == "userinfo";
+
+        // Server side entries, then the optional client side ones.
+        for side in 0..2 {
+            if side == 1 && !reader.read_bit()? {
+                break;
+            }
+
+            let entries = reader.read_bits(16)?;
+            for _ in 0..entries {
+                reader.read_string()?;
+
+                if !reader.read_bit()? {
+                    continue;
+                }
+
+                let len = reader.read_bits(16)? as usize;
+                let user_data = reader.read_bytes(len)?;
+
+                if is_user_info && side == 0 {
+                    if let Some(player) = parse_player_info(&user_data, tick) {
+                        players.push(player);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a `player_info_t`.
+fn parse_player_info(data: &[u8], tick: i64) -> Option<Player> {
+    if data.len() < 110 {
+        return None;
+    }
+
+    let steam_id = read_cstr(&data[36..69]);
+    let friends_id = u32::from_le_bytes(data[72..76].try_into().unwrap());
+
+    Some(Player {
+        name: read_cstr(&data[0..32]),
+        user_id: read_i32(data, 32),
+        account_id: if friends_id != 0 {
+            friends_id
+        } else {
+            account_id(&steam_id).unwrap_or(0)
+        },
+        steam_id,
+        fake_player: data[108] != 0,
+        hltv: data[109] != 0,
+        tick,
+    })
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_cstr(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
@@ -0,0 +1,215 @@
+use crate::generator::Clip;
+use crate::recording::RecordingProfile;
+use crate::segments::{RecordedSegment, RecordingKind};
+use crate::VDM;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// A recorded file placed on an editing timeline.
+#[derive(Debug, Clone)]
+pub struct EditClip {
+    pub name: String,
+    pub file: String,
+    pub frames: u64,
+}
+
+/// The recorded clips of a vdm, in order, for importing into an editor.
+#[derive(Debug, Clone)]
+pub struct EditList {
+    pub title: String,
+    pub fps: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Where the recordings are, usually the game directory. A relative root is taken from
+    /// the current directory.
+    pub media_root: PathBuf,
+    pub clips: Vec<EditClip>,
+}
+
+impl EditList {
+    pub fn new(title: impl Into<String>, fps: u32) -> Self {
+        EditList {
+            title: title.into(),
+            fps,
+            width: 1920,
+            height: 1080,
+            media_root: PathBuf::new(),
+            clips: vec![],
+        }
+    }
+
+    /// Uses the recordings the vdm makes, timed with the playback simulation.
+    pub fn from_vdm(vdm: &VDM, fps: u32, tick_rate: f64) -> Self {
+        let mut list = EditList::new(vdm.name.clone(), fps);
+
+        for segment in vdm.recorded_segments(tick_rate) {
+            list.add(&segment);
+        }
+
+        list
+    }
+
+    /// Uses clips as the generator would record them with a profile.
+    pub fn from_clips(
+        title: impl Into<String>,
+        clips: &[Clip],
+        profile: &RecordingProfile,
+        tick_rate: f64,
+    ) -> Self {
+        let mut list = EditList::new(title, profile.fps);
+
+        for (i, clip) in clips.iter().enumerate() {
+            list.add(&RecordedSegment {
+                kind: RecordingKind::Movie,
+                name: clip.name.clone(),
+                output: profile.output_name(i + 1),
+                flags: profile.formats.iter().map(|f| f.to_string()).collect(),
+                start_tick: clip.start_tick,
+                stop_tick: clip.stop_tick,
+                ranges: vec![(clip.start_tick, clip.stop_tick)],
+                duration: (clip.stop_tick - clip.start_tick) as f64 / tick_rate,
+            });
+        }
+
+        list
+    }
+
+    pub fn add(&mut self, segment: &RecordedSegment) {
+        self.clips.push(EditClip {
+            name: segment.name.clone(),
+            file: segment.file_name(),
+            frames: (segment.duration * self.fps as f64).round() as u64,
+        });
+    }
+
+    /// A CMX3600 edit decision list, with the record side starting at one hour.
+    pub fn to_edl(&self) -> String {
+        let mut edl = format!("TITLE: {}\r\nFCM: NON-DROP FRAME\r\n\r\n", self.title);
+        let mut record = self.fps as u64 * 3600;
+
+        for (i, clip) in self.clips.iter().enumerate() {
+            let _ = write!(
+                edl,
+                "{:03}  AX       AA/V  C        {} {} {} {}\r\n",
+                i + 1,
+                self.timecode(0),
+                self.timecode(clip.frames),
+                self.timecode(record),
+                self.timecode(record + clip.frames),
+            );
+            let _ = write!(edl, "* FROM CLIP NAME: {}\r\n", clip.file);
+            let _ = write!(edl, "* COMMENT: {}\r\n\r\n", clip.name);
+
+            record += clip.frames;
+        }
+
+        edl
+    }
+
+    /// An FCPXML 1.9 project with every clip back to back on the primary storyline.
+    ///
+    /// Assets point at the recordings under `media_root`.
+    pub fn to_fcpxml(&self) -> Result<String, &'static str> {
+        let fps = self.fps;
+        if fps == 0 {
+            return Err("Frame rate must be above 0");
+        }
+
+        let root = match self.media_root.is_absolute() {
+            true => self.media_root.clone(),
+            false => std::env::current_dir()
+                .map_err(|_| "Unable to find the media root")?
+                .join(&self.media_root),
+        };
+        let total = self.clips.iter().map(|clip| clip.frames).sum::<u64>();
+
+        let mut xml =
+            String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE fcpxml>\n");
+        xml.push_str("<fcpxml version=\"1.9\">\n\t<resources>\n");
+        let _ = writeln!(
+            xml,
+            "\t\t<format id=\"r0\" frameDuration=\"1/{fps}s\" width=\"{}\" height=\"{}\"/>",
+            self.width, self.height
+        );
+
+        for (i, clip) in self.clips.iter().enumerate() {
+            let _ = writeln!(
+                xml,
+                "\t\t<asset id=\"r{}\" name=\"{}\" src=\"{}\" start=\"0s\" duration=\"{}/{fps}s\" hasVideo=\"1\" hasAudio=\"1\" format=\"r0\"/>",
+                i + 1,
+                escape(&clip.name),
+                escape(&file_url(&root.join(&clip.file))),
+                clip.frames,
+            );
+        }
+
+        let title = escape(&self.title);
+        xml.push_str("\t</resources>\n\t<library>\n");
+        let _ = writeln!(xml, "\t\t<event name=\"{title}\">");
+        let _ = writeln!(xml, "\t\t\t<project name=\"{title}\">");
+        let _ = writeln!(
+            xml,
+            "\t\t\t\t<sequence format=\"r0\" duration=\"{total}/{fps}s\" tcStart=\"0s\" tcFormat=\"NDF\">"
+        );
+        xml.push_str("\t\t\t\t\t<spine>\n");
+
+        let mut offset = 0;
+        for (i, clip) in self.clips.iter().enumerate() {
+            let _ = writeln!(
+                xml,
+                "\t\t\t\t\t\t<asset-clip name=\"{}\" ref=\"r{}\" offset=\"{offset}/{fps}s\" duration=\"{}/{fps}s\" start=\"0s\" format=\"r0\"/>",
+                escape(&clip.name),
+                i + 1,
+                clip.frames,
+            );
+            offset += clip.frames;
+        }
+
+        xml.push_str("\t\t\t\t\t</spine>\n\t\t\t\t</sequence>\n\t\t\t</project>\n\t\t</event>\n\t</library>\n</fcpxml>\n");
+        Ok(xml)
+    }
+
+    fn timecode(&self, frames: u64) -> String {
+        let fps = self.fps.max(1) as u64;
+        let seconds = frames / fps;
+
+        format!(
+            "{:02}:{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            frames % fps
+        )
+    }
+}
+
+/// A `file://` URL for an absolute path, percent encoded.
+fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut url = String::from("file://");
+
+    // Windows paths start with their drive letter.
+    if !path.starts_with('/') {
+        url.push('/');
+    }
+
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                url.push(byte as char)
+            }
+            _ => {
+                let _ = write!(url, "%{byte:02X}");
+            }
+        }
+    }
+
+    url
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
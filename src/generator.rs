@@ -0,0 +1,251 @@
+use crate::action::{Action, ActionType};
+use crate::demo::{Player, Roster};
+use crate::playback::Playback;
+use crate::recording::RecordingProfile;
+use crate::sound;
+use crate::time::{Seconds, Tick};
+use crate::transitions::Transition;
+use crate::VDM;
+use serde::{Deserialize, Serialize};
+
+/// A labelled tick worth recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub tick: i64,
+    pub label: String,
+    /// SteamID of the player to spectate, in any format `Player::matches` accepts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<String>,
+}
+
+impl Bookmark {
+    pub fn new(tick: i64, label: impl Into<String>) -> Self {
+        Bookmark {
+            tick,
+            label: label.into(),
+            player: None,
+        }
+    }
+}
+
+/// A padded tick range that will be recorded as a single output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clip {
+    pub name: String,
+    pub start_tick: i64,
+    pub stop_tick: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<Bookmark>,
+    /// SteamID of the player to spectate during the clip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+impl Clip {
+    pub fn new(name: impl Into<String>, start_tick: i64, stop_tick: i64) -> Self {
+        Clip {
+            name: name.into(),
+            start_tick,
+            stop_tick,
+            bookmarks: vec![],
+            target: None,
+        }
+    }
+}
+
+/// How `spec_player` should refer to the spectated player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecTarget {
+    /// `spec_player #<userid>`, which keeps working after a name change.
+    UserId,
+    /// `spec_player <name>`, using the name from the roster at the clip start.
+    Name,
+}
+
+/// Turns bookmarks into a vdm that skips dead time and records the padded windows around them.
+///
+/// All distances are in ticks.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    /// Ticks recorded before each bookmark.
+    pub before: i64,
+    /// Ticks recorded after each bookmark.
+    pub after: i64,
+    /// Windows closer than this are merged into one clip.
+    pub merge_distance: i64,
+    /// Gaps shorter than this are played through instead of skipped.
+    pub min_skip_gap: i64,
+    pub profile: RecordingProfile,
+    /// The number of the first clip's recording, later clips count up from it.
+    pub first_number: usize,
+    /// Players to look clip targets up in, usually `Demo::roster`.
+    pub roster: Roster,
+    pub spec_mode: u8,
+    pub spec_target: SpecTarget,
+    /// Fades out at the end of each clip and back in at the start of the next.
+    pub transition: Option<Transition>,
+    /// Sounds and their lengths, played one after another from the first clip.
+    pub music: Vec<(String, Seconds)>,
+    /// Used to place transitions and music, their lengths are in seconds.
+    pub tick_rate: f64,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Generator {
+            before: 500,
+            after: 300,
+            merge_distance: 0,
+            min_skip_gap: 300,
+            profile: RecordingProfile::new(),
+            first_number: 1,
+            roster: Roster::default(),
+            spec_mode: 4,
+            spec_target: SpecTarget::UserId,
+            transition: None,
+            music: vec![],
+            tick_rate: 66.0,
+        }
+    }
+
+    pub fn clips(&self, bookmarks: &[Bookmark]) -> Vec<Clip> {
+        let clips = bookmarks
+            .iter()
+            .map(|bookmark| {
+                let mut clip = Clip::new(
+                    bookmark.label.clone(),
+                    (bookmark.tick - self.before).max(0),
+                    bookmark.tick + self.after,
+                );
+                clip.target = bookmark.player.clone();
+                clip.bookmarks.push(bookmark.clone());
+                clip
+            })
+            .collect::<Vec<_>>();
+
+        self.merge(&clips)
+    }
+
+    /// Sorts clips and joins the ones within `merge_distance` of each other, making them
+    /// ready for `compile`. Each clip left is one recording.
+    pub fn merge(&self, clips: &[Clip]) -> Vec<Clip> {
+        let mut clips = clips.to_vec();
+        clips.sort_by_key(|clip| clip.start_tick);
+
+        let mut merged: Vec<Clip> = vec![];
+
+        for clip in clips {
+            if let Some(last) = merged.last_mut() {
+                if clip.start_tick - last.stop_tick <= self.merge_distance {
+                    last.name = format!("{} + {}", last.name, clip.name);
+                    last.stop_tick = last.stop_tick.max(clip.stop_tick);
+                    last.target = last.target.take().or(clip.target);
+                    last.bookmarks.extend(clip.bookmarks);
+                    continue;
+                }
+            }
+
+            merged.push(clip);
+        }
+
+        merged
+    }
+
+    pub fn generate(&self, bookmarks: &[Bookmark]) -> VDM {
+        self.compile(&self.clips(bookmarks))
+    }
+
+    /// Clips with a target that isn't in the roster, which `compile` records without
+    /// spectating anyone.
+    pub fn unresolved<'a>(&self, clips: &'a [Clip]) -> Vec<&'a Clip> {
+        clips
+            .iter()
+            .filter(|clip| {
+                clip.target
+                    .as_ref()
+                    .is_some_and(|target| self.roster.find(target, clip.start_tick).is_none())
+            })
+            .collect()
+    }
+
+    /// Builds the vdm for clips that are already sorted and do not overlap.
+    ///
+    /// Check `unresolved` first to catch clips that won't spectate their target.
+    pub fn compile(&self, clips: &[Clip]) -> VDM {
+        let mut vdm = VDM::new();
+        let mut position = 1;
+
+        for (i, clip) in clips.iter().enumerate() {
+            if clip.start_tick - position >= self.min_skip_gap {
+                let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+                props.name = format!("Skip to {}", clip.name);
+                props.start = Tick(position).into();
+                props.skip_to = Tick(clip.start_tick - 1).into();
+            }
+
+            if let Some(player) = clip
+                .target
+                .as_ref()
+                .and_then(|target| self.roster.find(target, clip.start_tick))
+            {
+                let props = vdm.create_action(ActionType::PlayCommands).props_mut();
+                props.name = format!("Spectate {}", player.name);
+                props.start = Tick(clip.start_tick).into();
+                props.commands = self.spec_commands(player);
+            }
+
+            let (mut start, mut stop) =
+                self.profile
+                    .actions(self.first_number + i, clip.start_tick, clip.stop_tick);
+            start.props_mut().name = format!("Start {}", clip.name);
+            stop.props_mut().name = format!("Stop {}", clip.name);
+            vdm.add(start);
+
+            if let Some(transition) = &self.transition {
+                if i > 0 {
+                    vdm.add(transition.fade_in_action(Tick(clip.start_tick), self.tick_rate));
+                }
+
+                if i + 1 < clips.len() {
+                    vdm.add(transition.fade_out_action(Tick(clip.stop_tick), self.tick_rate));
+                }
+            }
+
+            vdm.add(stop);
+
+            position = clip.stop_tick + 1;
+        }
+
+        if let Some(first) = clips.first() {
+            let playback = Playback::new(&vdm, self.tick_rate);
+
+            for action in sound::back_to_back(&self.music, Tick(first.start_tick), &playback) {
+                vdm.add(action);
+            }
+        }
+
+        vdm
+    }
+
+    pub fn spec_commands(&self, player: &Player) -> String {
+        // Names with spaces or quotes can't be passed through a vdm, fall back to the userid.
+        let by_name = self.spec_target == SpecTarget::Name
+            && !player
+                .name
+                .contains(|c: char| c.is_whitespace() || c == '"' || c == ';');
+
+        let target = if by_name {
+            player.name.clone()
+        } else {
+            format!("#{}", player.user_id)
+        };
+
+        format!("spec_player {target}; spec_mode {}", self.spec_mode)
+    }
+}
@@ -0,0 +1,236 @@
+use crate::action::{Action, Properties};
+use crate::time::{Seconds, Tick};
+use crate::VDM;
+
+/// A reversible change to the actions of a vdm.
+#[derive(Debug, Clone)]
+pub enum Edit {
+    Insert {
+        index: usize,
+        action: Action,
+    },
+    Remove {
+        index: usize,
+        action: Action,
+    },
+    /// Shifts are stored as property changes, as shifting below zero clears the start.
+    SetProps {
+        index: usize,
+        old: Box<Properties>,
+        new: Box<Properties>,
+    },
+    Move {
+        from: usize,
+        to: usize,
+    },
+}
+
+impl Edit {
+    pub fn inverse(&self) -> Edit {
+        match self.clone() {
+            Edit::Insert { index, action } => Edit::Remove { index, action },
+            Edit::Remove { index, action } => Edit::Insert { index, action },
+            Edit::SetProps { index, old, new } => Edit::SetProps {
+                index,
+                old: new,
+                new: old,
+            },
+            Edit::Move { from, to } => Edit::Move { from: to, to: from },
+        }
+    }
+
+    fn apply(&self, vdm: &mut VDM) -> Change {
+        match self {
+            Edit::Insert { index, action } => {
+                vdm.actions.insert(*index, action.clone());
+                Change::Inserted(*index)
+            }
+            Edit::Remove { index, .. } => {
+                vdm.remove(*index);
+                Change::Removed(*index)
+            }
+            Edit::SetProps { index, new, .. } => {
+                vdm.set_nth_props(*index, (**new).clone());
+                Change::Changed(*index)
+            }
+            Edit::Move { from, to } => {
+                let action = vdm.actions.remove(*from);
+                vdm.actions.insert(*to, action);
+                Change::Moved {
+                    from: *from,
+                    to: *to,
+                }
+            }
+        }
+    }
+}
+
+/// Which rows an edit, undo or redo touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Inserted(usize),
+    Removed(usize),
+    Changed(usize),
+    Moved { from: usize, to: usize },
+}
+
+/// A vdm that records every change made through it so they can be undone.
+///
+/// Edits made between `begin` and `commit` are undone together, anything else is its own step.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    vdm: VDM,
+    undo: Vec<Vec<Edit>>,
+    redo: Vec<Vec<Edit>>,
+    transaction: Option<Vec<Edit>>,
+    depth: usize,
+    changes: Vec<Change>,
+}
+
+impl History {
+    pub fn new(vdm: VDM) -> Self {
+        History {
+            vdm,
+            ..Default::default()
+        }
+    }
+
+    pub fn vdm(&self) -> &VDM {
+        &self.vdm
+    }
+
+    pub fn into_vdm(self) -> VDM {
+        self.vdm
+    }
+
+    /// Starts a transaction, transactions can be nested and only the outermost one is recorded.
+    pub fn begin(&mut self) {
+        self.depth += 1;
+
+        if self.transaction.is_none() {
+            self.transaction = Some(vec![]);
+        }
+    }
+
+    pub fn commit(&mut self) {
+        if self.depth == 0 {
+            return;
+        }
+
+        self.depth -= 1;
+
+        if self.depth == 0 {
+            if let Some(edits) = self.transaction.take() {
+                if !edits.is_empty() {
+                    self.undo.push(edits);
+                }
+            }
+        }
+    }
+
+    /// Runs `f` as one transaction.
+    pub fn transaction<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.begin();
+        let result = f(self);
+        self.commit();
+        result
+    }
+
+    pub fn add(&mut self, action: Action) {
+        self.insert(self.vdm.len(), action);
+    }
+
+    pub fn insert(&mut self, index: usize, action: Action) {
+        self.record(Edit::Insert { index, action });
+    }
+
+    pub fn remove(&mut self, index: usize) -> Action {
+        let action = self.vdm.nth(index).clone();
+        self.record(Edit::Remove {
+            index,
+            action: action.clone(),
+        });
+        action
+    }
+
+    pub fn set_nth_props(&mut self, index: usize, new_props: Properties) {
+        self.record(Edit::SetProps {
+            index,
+            old: Box::new(self.vdm.nth(index).props()),
+            new: Box::new(new_props),
+        });
+    }
+
+    pub fn shift_by_tick(&mut self, index: usize, adjustment: Tick, tick_rate: f64) {
+        let mut props = self.vdm.nth(index).props();
+        props.shift_by_tick(adjustment, tick_rate);
+        self.set_nth_props(index, props);
+    }
+
+    pub fn shift_by_time(&mut self, index: usize, adjustment: Seconds, tick_rate: f64) {
+        let mut props = self.vdm.nth(index).props();
+        props.shift_by_time(adjustment, tick_rate);
+        self.set_nth_props(index, props);
+    }
+
+    /// Moves the action at `from` so it ends up at `to`.
+    pub fn move_action(&mut self, from: usize, to: usize) {
+        if from != to {
+            self.record(Edit::Move { from, to });
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Reverts the last step, returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edits) = self.undo.pop() else {
+            return false;
+        };
+
+        for edit in edits.iter().rev() {
+            let change = edit.inverse().apply(&mut self.vdm);
+            self.changes.push(change);
+        }
+
+        self.redo.push(edits);
+        true
+    }
+
+    /// Reapplies the last undone step, returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edits) = self.redo.pop() else {
+            return false;
+        };
+
+        for edit in &edits {
+            let change = edit.apply(&mut self.vdm);
+            self.changes.push(change);
+        }
+
+        self.undo.push(edits);
+        true
+    }
+
+    /// Everything that changed since the last call, in the order it happened.
+    pub fn take_changes(&mut self) -> Vec<Change> {
+        std::mem::take(&mut self.changes)
+    }
+
+    fn record(&mut self, edit: Edit) {
+        let change = edit.apply(&mut self.vdm);
+        self.changes.push(change);
+        self.redo.clear();
+
+        match &mut self.transaction {
+            Some(edits) => edits.push(edit),
+            None => self.undo.push(vec![edit]),
+        }
+    }
+}
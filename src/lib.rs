@@ -1,16 +1,42 @@
 #![allow(unused)] // Make it stop!
 pub mod action;
+pub mod assets;
+pub mod batch;
+pub mod beats;
+pub mod colour;
+pub mod demo;
+pub mod edl;
+pub mod generator;
+pub mod history;
+pub mod normalize;
+pub mod optimize;
+pub mod playback;
+pub mod project;
+pub mod ramp;
+pub mod recording;
+pub mod segments;
+pub mod sound;
+pub mod subtitles;
+pub mod sync;
+pub mod syntax;
 #[cfg(test)]
 mod tests;
-
-use action::{Action, ActionType, Properties};
-use regex::Regex;
+pub mod time;
+pub mod titles;
+pub mod transitions;
+pub mod validate;
+pub mod vpk;
+pub mod zoom;
+
+use action::{Action, ActionId, ActionType, Properties};
 use std::{ffi::OsStr, fmt::Display, fs, io::Write, path::Path};
 
 #[derive(Debug, Clone)]
 pub struct VDM {
     pub actions: Vec<Action>,
     pub name: String,
+    /// Write each action under the key it was parsed with instead of renumbering them.
+    pub keep_keys: bool,
 }
 
 impl Default for VDM {
@@ -24,9 +50,11 @@ impl VDM {
         VDM {
             actions: vec![],
             name: String::new(),
+            keep_keys: false,
         }
     }
 
+    /// Opens a vdm, failing if any of it can't be read so saving it again can't lose actions.
     pub fn open(file_path: impl AsRef<Path>) -> Result<VDM, &'static str> {
         let file_path = file_path.as_ref();
 
@@ -38,7 +66,7 @@ impl VDM {
                 }
             };
 
-            let mut vdm = VDM::from(file);
+            let mut vdm = syntax::parse(&file).try_to_vdm()?;
             vdm.name = file_path
                 .file_name()
                 .and_then(OsStr::to_str)
@@ -52,9 +80,14 @@ impl VDM {
         Err("Invalid file type")
     }
 
-    pub fn export(&self, file_path: impl AsRef<Path>) {
-        let mut vdm_file = fs::File::create(file_path).unwrap();
-        vdm_file.write_all(self.to_string().as_bytes()).unwrap();
+    pub fn export(&self, file_path: impl AsRef<Path>) -> Result<(), &'static str> {
+        let Ok(mut vdm_file) = fs::File::create(file_path) else {
+            return Err("Error Creating File.");
+        };
+
+        vdm_file
+            .write_all(self.to_string().as_bytes())
+            .map_err(|_| "Error Saving File.")
     }
 
     pub fn add(&mut self, action: Action) {
@@ -118,6 +151,24 @@ impl VDM {
         &mut self.actions[i]
     }
 
+    pub fn find(&self, id: ActionId) -> Option<&Action> {
+        self.actions.iter().find(|action| action.id() == id)
+    }
+
+    pub fn find_mut(&mut self, id: ActionId) -> Option<&mut Action> {
+        self.actions.iter_mut().find(|action| action.id() == id)
+    }
+
+    /// Where the action with this ID currently is.
+    pub fn position(&self, id: ActionId) -> Option<usize> {
+        self.actions.iter().position(|action| action.id() == id)
+    }
+
+    pub fn remove_id(&mut self, id: ActionId) -> Option<Action> {
+        let i = self.position(id)?;
+        Some(self.actions.remove(i))
+    }
+
     pub fn set_first(&mut self, new_action: Action) {
         self.actions[0] = new_action;
     }
@@ -147,28 +198,9 @@ impl VDM {
 
 impl<T: AsRef<str>> From<T> for VDM {
     fn from(file_text: T) -> Self {
-        let file_text = file_text.as_ref();
-
-        let mut vdm_actions = vec![];
-
-        let re = Regex::new(r"demoactions\r\n\{\r\n((.|\r\n)*)\r\n\}").unwrap();
-        let Some(actions) = re.captures(file_text) else {
-            return VDM::new();
-        };
-
-        let actions_split = actions[1].split("}");
-
-        for event in actions_split {
-            let main_body = event.split("{").collect::<Vec<_>>();
-            if main_body.len() > 1 {
-                vdm_actions.push(Action::from(main_body[1].to_string()));
-            }
-        }
-
-        VDM {
-            actions: vdm_actions,
-            name: String::new(),
-        }
+        // Blocks that don't make a valid action are left out, `syntax::Document::diagnostics` reports them.
+        // `VDM::open` refuses files like that instead.
+        syntax::parse(file_text.as_ref()).to_vdm()
     }
 }
 
@@ -176,8 +208,27 @@ impl Display for VDM {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "demoactions\r\n{{\r\n")?;
 
-        for (i, action) in self.actions.iter().enumerate() {
-            write!(f, "\t\"{}\"\r\n\t{{\r\n{action}\t}}\r\n", i + 1);
+        // Actions without a key, or with one already used, go after the highest numbered key so far.
+        let mut used = std::collections::HashSet::new();
+        let mut next = 1;
+
+        for action in &self.actions {
+            let key = match action.props().key {
+                Some(key) if self.keep_keys && !used.contains(&key) => key,
+                _ => {
+                    while used.contains(&next.to_string()) {
+                        next += 1;
+                    }
+                    next.to_string()
+                }
+            };
+
+            if let Ok(n) = key.parse::<u64>() {
+                next = next.max(n + 1);
+            }
+
+            write!(f, "\t\"{key}\"\r\n\t{{\r\n{action}\t}}\r\n");
+            used.insert(key);
         }
 
         write!(f, "}}\r\n")
@@ -0,0 +1,58 @@
+use crate::time::{TimePoint, TimeUnit};
+use crate::VDM;
+
+/// A time point that no longer lands on the same tick once written in the new unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rounding {
+    pub index: usize,
+    /// `start`, `stop` or `skipto`.
+    pub point: &'static str,
+    pub before: TimePoint,
+    pub after: TimePoint,
+}
+
+impl VDM {
+    /// Rewrites every start, stop and skip in `unit`, returning the points that had to be rounded.
+    pub fn normalize_timing(&mut self, unit: TimeUnit, tick_rate: f64) -> Vec<Rounding> {
+        let mut roundings = vec![];
+
+        for (index, action) in self.actions.iter_mut().enumerate() {
+            let props = action.props_mut();
+
+            for (point, time) in [
+                ("start", &mut props.start),
+                ("stop", &mut props.stop),
+                ("skipto", &mut props.skip_to),
+            ] {
+                let before = *time;
+                let after = before.to_unit(unit, tick_rate);
+
+                if !lands_on_same_tick(before, after, tick_rate) {
+                    roundings.push(Rounding {
+                        index,
+                        point,
+                        before,
+                        after,
+                    });
+                }
+
+                *time = after;
+            }
+        }
+
+        roundings
+    }
+}
+
+fn lands_on_same_tick(before: TimePoint, after: TimePoint, tick_rate: f64) -> bool {
+    let exact = |time: TimePoint| match time {
+        TimePoint::Unset => None,
+        TimePoint::Tick(tick) => Some(tick.0 as f64),
+        TimePoint::Time(time) => Some(time.0 * tick_rate),
+    };
+
+    match (exact(before), exact(after)) {
+        (Some(before), Some(after)) => (before - after).abs() < 1e-6,
+        _ => true,
+    }
+}
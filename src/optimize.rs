@@ -0,0 +1,258 @@
+use crate::action::{Action, ActionId};
+use crate::playback::{Playback, Segment};
+use crate::time::TimePoint;
+use crate::VDM;
+
+/// An action the optimizer removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimization {
+    pub id: ActionId,
+    pub name: String,
+    pub reason: &'static str,
+    /// The action that took over what this one did, if any.
+    pub merged_into: Option<ActionId>,
+}
+
+impl VDM {
+    /// Removes actions that do nothing and merges skips that follow each other.
+    ///
+    /// Every change is checked against the playback simulation, so segments, the final
+    /// playback rate and when every other action runs all stay the same.
+    pub fn optimize(&mut self, tick_rate: f64) -> Vec<Optimization> {
+        let mut report = vec![];
+
+        while let Some(optimization) = self.optimize_once(tick_rate) {
+            report.push(optimization);
+        }
+
+        report
+    }
+
+    fn optimize_once(&mut self, tick_rate: f64) -> Option<Optimization> {
+        let playback = Playback::new(self, tick_rate);
+        let before = Observed::new(self, &playback);
+
+        for (n, fired) in playback.fired.iter().enumerate() {
+            let action = self.nth(fired.index).clone();
+            let props = action.props();
+
+            let reason = match &action {
+                Action::PlayCommands(props) if props.commands.trim().is_empty() => {
+                    Some("Runs no commands")
+                }
+                Action::SkipAhead(props) if !props.skip_to.is_set() => Some("Has no skip target"),
+                Action::SkipAhead(_) => Some("Skips to a tick playback has already reached"),
+                Action::ChangePlaybackRate(_) => Some("Doesn't change the playback rate"),
+                Action::ScreenFadeStart(_) => playback.fired[..n]
+                    .iter()
+                    .any(|earlier| {
+                        earlier.tick == fired.tick && same_fade(self.nth(earlier.index), &action)
+                    })
+                    .then_some("Repeats a fade that starts on the same tick"),
+                _ => None,
+            };
+
+            if let Some(reason) = reason {
+                let mut candidate = self.clone();
+                candidate.remove(fired.index);
+
+                if before.matches(&Observed::simulate(&candidate, tick_rate), &[props.id]) {
+                    *self = candidate;
+                    return Some(Optimization {
+                        id: props.id,
+                        name: props.name,
+                        reason,
+                        merged_into: None,
+                    });
+                }
+            }
+
+            // A skip that runs as soon as another one lands can be folded into the first.
+            let Some(next) = playback.fired.get(n + 1) else {
+                continue;
+            };
+
+            if let (Action::SkipAhead(first), Action::SkipAhead(second)) =
+                (&action, self.nth(next.index))
+            {
+                let second = second.clone();
+                let mut merged = first.clone();
+                merged.skip_to = second.skip_to;
+
+                let mut candidate = self.clone();
+                candidate.set_nth_props(fired.index, merged);
+                candidate.remove(next.index);
+
+                if before.matches(&Observed::simulate(&candidate, tick_rate), &[second.id]) {
+                    *self = candidate;
+                    return Some(Optimization {
+                        id: second.id,
+                        name: second.name,
+                        reason: "Skips right after another skip",
+                        merged_into: Some(first.id),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Fades are the same if they differ in nothing but their name and start.
+fn same_fade(a: &Action, b: &Action) -> bool {
+    let strip = |action: &Action| {
+        let mut action = action.clone();
+        let props = action.props_mut();
+        props.name = String::new();
+        props.start = TimePoint::Unset;
+        action.to_string()
+    };
+
+    strip(a) == strip(b)
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+/// Everything about playback someone watching it could notice.
+struct Observed {
+    segments: Vec<Segment>,
+    fired: Vec<(ActionId, i64, f64)>,
+    end_rate: f64,
+    stopped: bool,
+}
+
+impl Observed {
+    fn simulate(vdm: &VDM, tick_rate: f64) -> Self {
+        Observed::new(vdm, &Playback::new(vdm, tick_rate))
+    }
+
+    fn new(vdm: &VDM, playback: &Playback) -> Self {
+        let mut segments: Vec<Segment> = vec![];
+
+        // Playing on at the same rate, or skipping again from where a skip landed, looks the same
+        // as doing it in one go.
+        for &segment in &playback.segments {
+            match (segments.last_mut(), segment) {
+                (
+                    Some(Segment::Play {
+                        stop_tick, rate, ..
+                    }),
+                    Segment::Play {
+                        start_tick,
+                        stop_tick: next_stop,
+                        rate: next_rate,
+                        ..
+                    },
+                ) if *stop_tick == start_tick && *rate == next_rate => *stop_tick = next_stop,
+                (
+                    Some(Segment::Skip {
+                        to_tick,
+                        start_time,
+                        ..
+                    }),
+                    Segment::Skip {
+                        from_tick,
+                        to_tick: next_to,
+                        start_time: next_start,
+                    },
+                ) if *to_tick == from_tick && close(*start_time, next_start) => *to_tick = next_to,
+                _ => segments.push(segment),
+            }
+        }
+
+        // Without a StopPlayback it plays on after the last action, however far that was.
+        if let Some(Segment::Play {
+            stop_tick, rate, ..
+        }) = segments.last_mut()
+        {
+            if !playback.stopped && *rate == playback.end_rate {
+                *stop_tick = i64::MAX;
+            }
+        }
+
+        Observed {
+            segments,
+            fired: playback
+                .fired
+                .iter()
+                .map(|fired| (vdm.nth(fired.index).id(), fired.tick, fired.time))
+                .collect(),
+            end_rate: playback.end_rate,
+            stopped: playback.stopped,
+        }
+    }
+
+    /// Whether `other` plays the same, ignoring when the `removed` actions ran.
+    fn matches(&self, other: &Observed, removed: &[ActionId]) -> bool {
+        let fired = self
+            .fired
+            .iter()
+            .filter(|(id, ..)| !removed.contains(id))
+            .collect::<Vec<_>>();
+
+        self.segments.len() == other.segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(&other.segments)
+                .all(|(a, b)| same_segment(a, b))
+            && self.end_rate == other.end_rate
+            && self.stopped == other.stopped
+            && fired.len() == other.fired.len()
+            && fired
+                .iter()
+                .zip(&other.fired)
+                .all(|(a, b)| a.0 == b.0 && a.1 == b.1 && close(a.2, b.2))
+    }
+}
+
+fn same_segment(a: &Segment, b: &Segment) -> bool {
+    match (*a, *b) {
+        (
+            Segment::Play {
+                start_tick,
+                stop_tick,
+                rate,
+                start_time,
+            },
+            Segment::Play {
+                start_tick: other_start,
+                stop_tick: other_stop,
+                rate: other_rate,
+                start_time: other_time,
+            },
+        ) => {
+            (start_tick, stop_tick) == (other_start, other_stop)
+                && rate == other_rate
+                && close(start_time, other_time)
+        }
+        (
+            Segment::Pause {
+                tick,
+                duration,
+                start_time,
+            },
+            Segment::Pause {
+                tick: other_tick,
+                duration: other_duration,
+                start_time: other_time,
+            },
+        ) => tick == other_tick && duration == other_duration && close(start_time, other_time),
+        (
+            Segment::Skip {
+                from_tick,
+                to_tick,
+                start_time,
+            },
+            Segment::Skip {
+                from_tick: other_from,
+                to_tick: other_to,
+                start_time: other_time,
+            },
+        ) => (from_tick, to_tick) == (other_from, other_to) && close(start_time, other_time),
+        _ => false,
+    }
+}
@@ -0,0 +1,228 @@
+use crate::action::Action;
+use crate::time::Tick;
+use crate::VDM;
+
+/// A stretch of demo playback.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// Ticks played at a playback rate, starting `start_time` seconds into playback.
+    Play {
+        start_tick: i64,
+        stop_tick: i64,
+        rate: f64,
+        start_time: f64,
+    },
+    Pause {
+        tick: i64,
+        duration: f64,
+        start_time: f64,
+    },
+    Skip {
+        from_tick: i64,
+        to_tick: i64,
+        start_time: f64,
+    },
+}
+
+/// When an action was run by the demo player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fired {
+    pub index: usize,
+    pub tick: i64,
+    pub time: f64,
+}
+
+/// A simulation of how the demo player runs a vdm.
+///
+/// Actions run once playback reaches their start tick, actions that were skipped over run as
+/// soon as the skip lands. Times are wall-clock seconds since playback started.
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub tick_rate: f64,
+    pub segments: Vec<Segment>,
+    pub fired: Vec<Fired>,
+    /// Where playback is after the last action, it carries on at `end_rate` unless stopped.
+    pub end_tick: i64,
+    pub end_time: f64,
+    pub end_rate: f64,
+    pub stopped: bool,
+}
+
+enum Event {
+    Run(usize),
+    ResetRate,
+}
+
+impl Playback {
+    pub fn new(vdm: &VDM, tick_rate: f64) -> Self {
+        let mut events = vec![];
+
+        for (i, action) in vdm.actions.iter().enumerate() {
+            let props = action.props();
+            events.push((
+                props.start.ticks(tick_rate).map_or(0, |tick| tick.0),
+                Event::Run(i),
+            ));
+
+            if let Action::ChangePlaybackRate(props) = action {
+                if let Some(Tick(stop_tick)) = props.stop.ticks(tick_rate) {
+                    events.push((stop_tick, Event::ResetRate));
+                }
+            }
+        }
+
+        // Stable, so actions on the same tick keep their order in the file.
+        events.sort_by_key(|(tick, _)| *tick);
+
+        let mut playback = Playback {
+            tick_rate,
+            segments: vec![],
+            fired: vec![],
+            end_tick: 0,
+            end_time: 0.0,
+            end_rate: 1.0,
+            stopped: false,
+        };
+
+        for (tick, event) in events {
+            playback.play_until(tick);
+
+            match event {
+                Event::ResetRate => playback.end_rate = 1.0,
+                Event::Run(index) => {
+                    playback.fired.push(Fired {
+                        index,
+                        tick: playback.end_tick,
+                        time: playback.end_time,
+                    });
+
+                    if !playback.run(&vdm.actions[index]) {
+                        playback.stopped = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        playback
+    }
+
+    fn play_until(&mut self, tick: i64) {
+        if tick <= self.end_tick {
+            return;
+        }
+
+        self.segments.push(Segment::Play {
+            start_tick: self.end_tick,
+            stop_tick: tick,
+            rate: self.end_rate,
+            start_time: self.end_time,
+        });
+
+        self.end_time += (tick - self.end_tick) as f64 / self.tick_rate / self.end_rate;
+        self.end_tick = tick;
+    }
+
+    /// Applies an action, returns false once playback has stopped.
+    fn run(&mut self, action: &Action) -> bool {
+        match action {
+            Action::SkipAhead(props) => {
+                if let Some(Tick(to_tick)) = props.skip_to.ticks(self.tick_rate) {
+                    if to_tick > self.end_tick {
+                        self.segments.push(Segment::Skip {
+                            from_tick: self.end_tick,
+                            to_tick,
+                            start_time: self.end_time,
+                        });
+                        self.end_tick = to_tick;
+                    }
+                }
+            }
+            Action::Pause(props) => {
+                self.segments.push(Segment::Pause {
+                    tick: self.end_tick,
+                    duration: props.duration,
+                    start_time: self.end_time,
+                });
+                self.end_time += props.duration.max(0.0);
+            }
+            Action::ChangePlaybackRate(props) if props.playback_rate > 0.0 => {
+                self.end_rate = props.playback_rate;
+            }
+            Action::StopPlayback(_) => return false,
+            _ => {}
+        }
+
+        true
+    }
+
+    /// When playback first reaches a tick, `None` if it is skipped over or never reached.
+    pub fn time_at(&self, tick: i64) -> Option<f64> {
+        for segment in &self.segments {
+            if let Segment::Play {
+                start_tick,
+                stop_tick,
+                rate,
+                start_time,
+            } = *segment
+            {
+                if (start_tick..=stop_tick).contains(&tick) {
+                    return Some(start_time + (tick - start_tick) as f64 / self.tick_rate / rate);
+                }
+            }
+        }
+
+        if tick == self.end_tick || (tick > self.end_tick && !self.stopped) {
+            return Some(
+                self.end_time + (tick - self.end_tick) as f64 / self.tick_rate / self.end_rate,
+            );
+        }
+
+        None
+    }
+
+    /// The tick playback is on at a wall-clock time, `None` if it has stopped by then.
+    pub fn tick_at(&self, time: f64) -> Option<i64> {
+        for segment in &self.segments {
+            match *segment {
+                Segment::Play {
+                    start_tick,
+                    stop_tick,
+                    rate,
+                    start_time,
+                } => {
+                    let stop_time =
+                        start_time + (stop_tick - start_tick) as f64 / self.tick_rate / rate;
+
+                    if (start_time..stop_time).contains(&time) {
+                        let played = (time - start_time) * self.tick_rate * rate;
+                        return Some(start_tick + played.round() as i64);
+                    }
+                }
+                Segment::Pause {
+                    tick,
+                    duration,
+                    start_time,
+                } if (start_time..start_time + duration).contains(&time) => return Some(tick),
+                _ => {}
+            }
+        }
+
+        if time == self.end_time || (time > self.end_time && !self.stopped) {
+            let played = (time - self.end_time) * self.tick_rate * self.end_rate;
+            return Some(self.end_tick + played.round() as i64);
+        }
+
+        None
+    }
+
+    /// Wall-clock seconds between reaching two ticks.
+    pub fn duration(&self, start_tick: i64, stop_tick: i64) -> Option<f64> {
+        Some(self.time_at(stop_tick)? - self.time_at(start_tick)?)
+    }
+
+    /// When the action at `index` ran, `None` if playback stopped first.
+    pub fn fired_at(&self, index: usize) -> Option<&Fired> {
+        self.fired.iter().find(|fired| fired.index == index)
+    }
+}
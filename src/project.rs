@@ -0,0 +1,224 @@
+use crate::demo::Demo;
+use crate::generator::{Clip, Generator};
+use crate::recording::RecordingProfile;
+use crate::VDM;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A demo in a project, with the clips to record from it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectDemo {
+    /// Relative to the project file.
+    pub path: PathBuf,
+    pub notes: String,
+    /// Used instead of the project's profile.
+    pub profile: Option<RecordingProfile>,
+    /// Recorded in this order.
+    pub clips: Vec<Clip>,
+    /// A hash of the vdm as it was last generated, to tell when it's been edited by hand.
+    pub generated: Option<String>,
+    /// The demo's vdm, which the game loads from next to the demo.
+    #[serde(skip)]
+    pub vdm: VDM,
+}
+
+impl ProjectDemo {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        ProjectDemo {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Everything that goes into a movie, saved as TOML or JSON.
+///
+/// ```toml
+/// name = "Frag movie"
+///
+/// [profile]
+/// fps = 60
+///
+/// [[demos]]
+/// path = "demos/match1.dem"
+/// clips = [{ name = "triple", start_tick = 4200, stop_tick = 5000 }]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub name: String,
+    pub notes: String,
+    pub profile: RecordingProfile,
+    pub demos: Vec<ProjectDemo>,
+    /// The directory demo paths are relative to, where the project file is.
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+/// What `Project::regenerate` did with a demo's vdm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regenerated {
+    pub path: PathBuf,
+    /// The vdm on disk had been changed since it was generated.
+    pub hand_edited: bool,
+    pub written: bool,
+}
+
+impl Project {
+    pub fn new(name: impl Into<String>) -> Self {
+        Project {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Opens a `.toml` or `.json` project, and the vdms next to its demos.
+    pub fn open(file_path: impl AsRef<Path>) -> Result<Project, &'static str> {
+        let file_path = file_path.as_ref();
+
+        let Ok(text) = fs::read_to_string(file_path) else {
+            return Err("Error Opening File.");
+        };
+
+        let mut project = match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Project::from_toml(text)?,
+            Some("json") => Project::from_json(text)?,
+            _ => return Err("Invalid file type"),
+        };
+
+        project.root = file_path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        for i in 0..project.demos.len() {
+            let vdm_path = project.vdm_path(i);
+            if vdm_path.is_file() {
+                project.demos[i].vdm = VDM::open(vdm_path)?;
+            }
+        }
+
+        Ok(project)
+    }
+
+    pub fn from_toml(text: impl AsRef<str>) -> Result<Project, &'static str> {
+        toml::from_str(text.as_ref()).map_err(|_| "Invalid project")
+    }
+
+    pub fn from_json(text: impl AsRef<str>) -> Result<Project, &'static str> {
+        serde_json::from_str(text.as_ref()).map_err(|_| "Invalid project")
+    }
+
+    pub fn to_toml(&self) -> Result<String, &'static str> {
+        toml::to_string_pretty(self).map_err(|_| "Unable to write project")
+    }
+
+    pub fn to_json(&self) -> Result<String, &'static str> {
+        serde_json::to_string_pretty(self).map_err(|_| "Unable to write project")
+    }
+
+    /// Saves as TOML or JSON, going by the extension. The vdms are written by `regenerate`.
+    pub fn save(&self, file_path: impl AsRef<Path>) -> Result<(), &'static str> {
+        let file_path = file_path.as_ref();
+
+        let text = match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => self.to_toml()?,
+            Some("json") => self.to_json()?,
+            _ => return Err("Invalid file type"),
+        };
+
+        fs::write(file_path, text).map_err(|_| "Error Saving File.")
+    }
+
+    pub fn demo_path(&self, index: usize) -> PathBuf {
+        self.root.join(&self.demos[index].path)
+    }
+
+    /// Where the game looks for a demo's vdm, next to it with the same name.
+    pub fn vdm_path(&self, index: usize) -> PathBuf {
+        self.demo_path(index).with_extension("vdm")
+    }
+
+    /// Whether the vdm on disk isn't the one last generated. A vdm that was never generated
+    /// counts as hand made.
+    pub fn is_hand_edited(&self, index: usize) -> bool {
+        let Ok(text) = fs::read(self.vdm_path(index)) else {
+            return false;
+        };
+
+        self.demos[index].generated.as_deref() != Some(&hash(&text))
+    }
+
+    fn generator(&self, index: usize) -> Generator {
+        let mut generator = Generator::new();
+        generator.profile = self.demos[index]
+            .profile
+            .clone()
+            .unwrap_or(self.profile.clone());
+        generator
+    }
+
+    /// The vdm a demo's clips compile to, with overlapping clips merged. Recordings are
+    /// numbered across the whole project.
+    pub fn generate(&self, index: usize) -> VDM {
+        let demo = &self.demos[index];
+
+        let mut generator = self.generator(index);
+        generator.first_number = 1
+            + (0..index)
+                .map(|i| self.generator(i).merge(&self.demos[i].clips).len())
+                .sum::<usize>();
+
+        if demo.clips.iter().any(|clip| clip.target.is_some()) {
+            if let Ok(file) = Demo::open(self.demo_path(index)) {
+                generator.roster = file.roster;
+            }
+        }
+
+        let mut vdm = generator.compile(&generator.merge(&demo.clips));
+        vdm.name = demo
+            .path
+            .file_stem()
+            .map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+        vdm
+    }
+
+    /// Writes every demo's vdm from its clips, leaving hand edited ones alone unless
+    /// `overwrite_edits` is set. Save the project afterwards to keep track of what was written.
+    pub fn regenerate(&mut self, overwrite_edits: bool) -> Result<Vec<Regenerated>, &'static str> {
+        let mut report = vec![];
+
+        for i in 0..self.demos.len() {
+            let path = self.vdm_path(i);
+            let hand_edited = self.is_hand_edited(i);
+            let written = !hand_edited || overwrite_edits;
+
+            if written {
+                let vdm = self.generate(i);
+                let text = vdm.to_string();
+
+                fs::write(&path, &text).map_err(|_| "Error Saving File.")?;
+
+                let demo = &mut self.demos[i];
+                demo.generated = Some(hash(text.as_bytes()));
+                demo.vdm = vdm;
+            }
+
+            report.push(Regenerated {
+                path,
+                hand_edited,
+                written,
+            });
+        }
+
+        Ok(report)
+    }
+}
+
+/// FNV-1a, as hex.
+fn hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+
+    format!("{hash:016x}")
+}
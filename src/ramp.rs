@@ -0,0 +1,139 @@
+use crate::action::{Action, ActionType};
+use crate::playback::Playback;
+use crate::time::Tick;
+use crate::VDM;
+
+/// How the playback rate moves from `from_rate` to `to_rate` over a ramp.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// `(position, rate)` points, positions go from 0 at the start of the ramp to 1 at the end.
+    /// Rates between points are interpolated linearly and `from_rate` and `to_rate` are unused.
+    Keyframes(Vec<(f64, f64)>),
+}
+
+impl Curve {
+    /// The rate at `position` through the ramp, between 0 and 1.
+    pub fn rate(&self, position: f64, from_rate: f64, to_rate: f64) -> f64 {
+        let t = position.clamp(0.0, 1.0);
+        let eased = match self {
+            Curve::Linear => t,
+            Curve::EaseIn => t * t,
+            Curve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Curve::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Curve::Keyframes(keyframes) => return interpolate(keyframes, t).unwrap_or(from_rate),
+        };
+
+        from_rate + (to_rate - from_rate) * eased
+    }
+}
+
+fn interpolate(keyframes: &[(f64, f64)], t: f64) -> Option<f64> {
+    let (first, last) = (keyframes.first()?, keyframes.last()?);
+
+    if t <= first.0 {
+        return Some(first.1);
+    }
+
+    for pair in keyframes.windows(2) {
+        let ((a, a_rate), (b, b_rate)) = (pair[0], pair[1]);
+
+        if t <= b {
+            let along = if b > a { (t - a) / (b - a) } else { 1.0 };
+            return Some(a_rate + (b_rate - a_rate) * along);
+        }
+    }
+
+    Some(last.1)
+}
+
+/// A change in playback rate over a range of ticks, made of `ChangePlaybackRate` steps.
+#[derive(Debug, Clone)]
+pub struct SpeedRamp {
+    pub name: String,
+    pub start: Tick,
+    pub stop: Tick,
+    pub from_rate: f64,
+    pub to_rate: f64,
+    pub curve: Curve,
+    /// Ticks between rate changes, smaller steps follow the curve more closely.
+    pub step: Tick,
+    /// Go back to normal speed at `stop`, otherwise the last rate carries on.
+    pub reset: bool,
+}
+
+/// The engine ignores rates of zero or below.
+const MIN_RATE: f64 = 0.01;
+
+impl SpeedRamp {
+    pub fn new(start: Tick, stop: Tick, from_rate: f64, to_rate: f64) -> Self {
+        SpeedRamp {
+            name: "Speed ramp".to_string(),
+            start,
+            stop,
+            from_rate,
+            to_rate,
+            curve: Curve::Linear,
+            step: Tick(6),
+            reset: true,
+        }
+    }
+
+    /// One `ChangePlaybackRate` per step, each using the curve's rate halfway through it.
+    ///
+    /// Steps with the same rate as the one before are left out.
+    pub fn actions(&self) -> Vec<Action> {
+        let length = (self.stop - self.start).0;
+        let step = self.step.0.max(1);
+        let mut actions: Vec<Action> = vec![];
+        let mut last_rate = None;
+
+        let mut tick = 0;
+        while tick < length {
+            let middle = (tick as f64 + (step.min(length - tick) as f64) / 2.0) / length as f64;
+            let rate = self
+                .curve
+                .rate(middle, self.from_rate, self.to_rate)
+                .max(MIN_RATE);
+
+            if last_rate != Some(rate) {
+                let mut action = Action::new(ActionType::ChangePlaybackRate);
+                let props = action.props_mut();
+                props.name = format!("{} {}", self.name, actions.len() + 1);
+                props.start = (self.start + Tick(tick)).into();
+                props.playback_rate = rate;
+                actions.push(action);
+                last_rate = Some(rate);
+            }
+
+            tick += step;
+        }
+
+        if self.reset {
+            if let Some(last) = actions.last_mut() {
+                last.props_mut().stop = self.stop.into();
+            }
+        }
+
+        actions
+    }
+
+    /// How long the ramp takes to watch, in seconds.
+    pub fn duration(&self, tick_rate: f64) -> f64 {
+        let mut vdm = VDM::new();
+        self.add_to(&mut vdm, tick_rate).unwrap_or(0.0)
+    }
+
+    /// Adds the ramp's actions, returning how long the ramp takes to watch with everything
+    /// else in the vdm. `None` if playback never gets through it.
+    pub fn add_to(&self, vdm: &mut VDM, tick_rate: f64) -> Option<f64> {
+        for action in self.actions() {
+            vdm.add(action);
+        }
+
+        Playback::new(vdm, tick_rate).duration(self.start.0, self.stop.0)
+    }
+}
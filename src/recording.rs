@@ -0,0 +1,159 @@
+use crate::action::{Action, ActionType};
+use crate::time::Tick;
+use crate::VDM;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    path::Path,
+};
+
+/// Output formats understood by `startmovie`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Tga,
+    Jpeg,
+    Wav,
+    H264,
+    Raw,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let repr: &str = From::from(*self);
+        write!(f, "{repr}")
+    }
+}
+
+impl From<OutputFormat> for &'static str {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Tga => "tga",
+            OutputFormat::Jpeg => "jpeg",
+            OutputFormat::Wav => "wav",
+            OutputFormat::H264 => "h264",
+            OutputFormat::Raw => "raw",
+        }
+    }
+}
+
+/// The commands needed to record a clip, shared between every clip in a render.
+///
+/// `name_pattern` is used to name each output, `{n}` is replaced with the clip number.
+///
+/// ```toml
+/// fps = 60
+/// formats = ["tga", "wav"]
+/// name_pattern = "frag_{n}"
+/// pre_commands = ["cl_drawhud 0"]
+/// post_commands = ["cl_drawhud 1"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingProfile {
+    pub fps: u32,
+    pub formats: Vec<OutputFormat>,
+    pub name_pattern: String,
+    pub pre_commands: Vec<String>,
+    pub post_commands: Vec<String>,
+}
+
+impl Default for RecordingProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordingProfile {
+    pub fn new() -> Self {
+        RecordingProfile {
+            fps: 60,
+            formats: vec![OutputFormat::Tga, OutputFormat::Wav],
+            name_pattern: "clip_{n}".to_string(),
+            pre_commands: vec![],
+            post_commands: vec![],
+        }
+    }
+
+    pub fn open(file_path: impl AsRef<Path>) -> Result<RecordingProfile, &'static str> {
+        let file_path = file_path.as_ref();
+
+        if file_path.extension().is_some_and(|ext| ext == "toml") {
+            let Ok(file) = fs::read_to_string(file_path) else {
+                return Err("Error Opening File.");
+            };
+
+            return RecordingProfile::from_toml(file);
+        }
+
+        Err("Invalid file type")
+    }
+
+    pub fn from_toml(text: impl AsRef<str>) -> Result<RecordingProfile, &'static str> {
+        toml::from_str(text.as_ref()).map_err(|_| "Invalid recording profile")
+    }
+
+    /// The output name of the `n`th clip, counting from 1.
+    pub fn output_name(&self, n: usize) -> String {
+        self.name_pattern.replace("{n}", &n.to_string())
+    }
+
+    pub fn start_commands(&self, n: usize) -> String {
+        let mut commands = self.pre_commands.clone();
+
+        commands.push(format!("host_framerate {}", self.fps));
+
+        let mut startmovie = format!("startmovie {}", self.output_name(n));
+        for format in &self.formats {
+            startmovie.push_str(&format!(" {format}"));
+        }
+        commands.push(startmovie);
+
+        commands.join("; ")
+    }
+
+    pub fn stop_commands(&self) -> String {
+        let mut commands = vec!["endmovie".to_string(), "host_framerate 0".to_string()];
+        commands.extend(self.post_commands.iter().cloned());
+
+        commands.join("; ")
+    }
+
+    /// Creates the `PlayCommands` pair that records the `n`th clip between two ticks.
+    pub fn actions(&self, n: usize, start_tick: i64, stop_tick: i64) -> (Action, Action) {
+        let name = self.output_name(n);
+
+        let mut start = Action::new(ActionType::PlayCommands);
+        let props = start.props_mut();
+        props.name = format!("Start {name}");
+        props.start = Tick(start_tick).into();
+        props.commands = self.start_commands(n);
+
+        let mut stop = Action::new(ActionType::PlayCommands);
+        let props = stop.props_mut();
+        props.name = format!("Stop {name}");
+        props.start = Tick(stop_tick).into();
+        props.commands = self.stop_commands();
+
+        (start, stop)
+    }
+
+    /// Appends a recording to the vdm, numbered after the recordings it already contains.
+    ///
+    /// Returns the output name of the new recording.
+    pub fn record(&self, vdm: &mut VDM, start_tick: i64, stop_tick: i64) -> String {
+        let n = vdm
+            .actions
+            .iter()
+            .filter(|action| matches!(action, Action::PlayCommands(props) if props.commands.contains("startmovie")))
+            .count()
+            + 1;
+
+        let (start, stop) = self.actions(n, start_tick, stop_tick);
+        vdm.add(start);
+        vdm.add(stop);
+
+        self.output_name(n)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::action::Action;
+use crate::playback::{Playback, Segment};
+use crate::VDM;
+
+/// How a stretch of the demo was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingKind {
+    /// `startmovie` and `endmovie`.
+    Movie,
+    /// `startrecording` and `stoprecording`.
+    Recording,
+    /// `record` or `demo_record`, and `stop` or `demo_stop`, which write a new demo.
+    Demo,
+}
+
+impl RecordingKind {
+    /// The kind a command starts, then the kind it stops.
+    fn from_command(command: &str) -> (Option<Self>, Option<Self>) {
+        match command.to_lowercase().as_str() {
+            "startmovie" => (Some(RecordingKind::Movie), None),
+            "endmovie" => (None, Some(RecordingKind::Movie)),
+            "startrecording" => (Some(RecordingKind::Recording), None),
+            "stoprecording" => (None, Some(RecordingKind::Recording)),
+            "record" | "demo_record" => (Some(RecordingKind::Demo), None),
+            "stop" | "demo_stop" => (None, Some(RecordingKind::Demo)),
+            _ => (None, None),
+        }
+    }
+}
+
+/// A stretch of the demo that a vdm records to disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedSegment {
+    pub kind: RecordingKind,
+    /// The name of the action that started the recording.
+    pub name: String,
+    /// The file name given to the command, can be empty for `startrecording`.
+    pub output: String,
+    pub flags: Vec<String>,
+    pub start_tick: i64,
+    pub stop_tick: i64,
+    /// The ticks that end up in the footage, split where playback skips ahead.
+    pub ranges: Vec<(i64, i64)>,
+    /// Wall-clock seconds between starting and stopping, which is how long the footage runs.
+    pub duration: f64,
+}
+
+impl RecordedSegment {
+    /// The file the game writes, image sequences are named after their first frame.
+    pub fn file_name(&self) -> String {
+        let has = |flag: &str| self.flags.iter().any(|f| f == flag);
+
+        if self.kind == RecordingKind::Demo {
+            format!("{}.dem", self.output.trim_end_matches(".dem"))
+        } else if has("h264") {
+            format!("{}.mp4", self.output)
+        } else if has("tga") || has("jpeg") || has("jpg") {
+            let ext = if has("tga") { "tga" } else { "jpg" };
+            format!("{}0000.{ext}", self.output)
+        } else if has("wav") {
+            format!("{}.wav", self.output)
+        } else {
+            self.output.clone()
+        }
+    }
+
+    /// How many ticks of the demo are in the footage.
+    pub fn ticks(&self) -> i64 {
+        self.ranges.iter().map(|(start, stop)| stop - start).sum()
+    }
+}
+
+/// The ticks played between two ticks, joining up stretches played back to back.
+fn played_ranges(playback: &Playback, start_tick: i64, stop_tick: i64) -> Vec<(i64, i64)> {
+    let mut ranges: Vec<(i64, i64)> = vec![];
+
+    for segment in &playback.segments {
+        let Segment::Play {
+            start_tick: from,
+            stop_tick: to,
+            ..
+        } = *segment
+        else {
+            continue;
+        };
+
+        let (from, to) = (from.max(start_tick), to.min(stop_tick));
+        if from >= to {
+            continue;
+        }
+
+        match ranges.last_mut() {
+            Some(last) if last.1 == from => last.1 = to,
+            _ => ranges.push((from, to)),
+        }
+    }
+
+    ranges
+}
+
+impl VDM {
+    /// Finds the recordings made by `PlayCommands`, in the order playback starts them.
+    ///
+    /// Each kind of recording is tracked on its own, so a demo can be recorded while a movie
+    /// is. Recordings still going when playback is stopped end there, ones that are never
+    /// stopped are left out.
+    pub fn recorded_segments(&self, tick_rate: f64) -> Vec<RecordedSegment> {
+        let playback = Playback::new(self, tick_rate);
+        let mut segments = vec![];
+        let mut recording: Vec<(RecordedSegment, f64)> = vec![];
+
+        let mut finish = |(mut segment, start_time): (RecordedSegment, f64), tick, time| {
+            segment.stop_tick = tick;
+            segment.ranges = played_ranges(&playback, segment.start_tick, tick);
+            segment.duration = time - start_time;
+            segments.push(segment);
+        };
+
+        for fired in &playback.fired {
+            let props = match &self.actions[fired.index] {
+                Action::PlayCommands(props) => props,
+                Action::StopPlayback(_) => {
+                    for open in recording.drain(..) {
+                        finish(open, fired.tick, fired.time);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            for command in props.commands.split(';') {
+                let mut args = command.split_whitespace().map(|arg| arg.trim_matches('"'));
+                let Some(command) = args.next() else {
+                    continue;
+                };
+
+                match RecordingKind::from_command(command) {
+                    (Some(kind), _) if recording.iter().all(|(open, _)| open.kind != kind) => {
+                        let output = args.next().unwrap_or_default();
+                        if output.is_empty() && kind != RecordingKind::Recording {
+                            continue;
+                        }
+
+                        let segment = RecordedSegment {
+                            kind,
+                            name: props.name.clone(),
+                            output: output.to_string(),
+                            flags: args.map(str::to_string).collect(),
+                            start_tick: fired.tick,
+                            stop_tick: fired.tick,
+                            ranges: vec![],
+                            duration: 0.0,
+                        };
+                        recording.push((segment, fired.time));
+                    }
+                    (_, Some(kind)) => {
+                        if let Some(i) = recording.iter().position(|(open, _)| open.kind == kind) {
+                            finish(recording.remove(i), fired.tick, fired.time);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        segments.sort_by_key(|segment| segment.start_tick);
+        segments
+    }
+}
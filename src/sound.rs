@@ -0,0 +1,272 @@
+use crate::action::{Action, ActionType};
+use crate::assets::Assets;
+use crate::playback::Playback;
+use crate::time::{Seconds, Tick};
+use crate::validate::{Issue, Severity};
+use crate::vpk::Vpk;
+use crate::VDM;
+use std::fs;
+use std::path::Path;
+
+/// How long a local `.wav` or `.mp3` file plays for.
+pub fn open(file_path: impl AsRef<Path>) -> Result<Seconds, &'static str> {
+    let file_path = file_path.as_ref();
+    let extension = file_path
+        .extension()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase();
+
+    if extension != "wav" && extension != "mp3" {
+        return Err("File is not a .wav or .mp3 file");
+    }
+
+    duration(&fs::read(file_path).map_err(|_| "Unable to read sound")?)
+}
+
+/// How long a sound inside a vpk plays for.
+pub fn in_vpk(vpk: &Vpk, path: &str) -> Result<Seconds, &'static str> {
+    duration(&vpk.read(path)?)
+}
+
+/// Reads the length of a sound from its WAV or MP3 headers.
+pub fn duration(data: &[u8]) -> Result<Seconds, &'static str> {
+    if data.starts_with(b"RIFF") {
+        wav_duration(data)
+    } else {
+        mp3_duration(data)
+    }
+}
+
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+pub fn wav_duration(data: &[u8]) -> Result<Seconds, &'static str> {
+    if data.get(8..12) != Some(b"WAVE") {
+        return Err("Not a wav file");
+    }
+
+    let mut format = None;
+    let mut samples = None;
+    let mut data_size = None;
+    let mut at = 12;
+
+    while let (Some(id), Some(size)) = (data.get(at..at + 4), u32_at(data, at + 4)) {
+        let body = at + 8;
+
+        match id {
+            b"fmt " => {
+                format = Some((
+                    u16_at(data, body).ok_or("Truncated wav")?,
+                    u32_at(data, body + 4).ok_or("Truncated wav")?,
+                    u32_at(data, body + 8).ok_or("Truncated wav")?,
+                ))
+            }
+            b"fact" => samples = u32_at(data, body),
+            b"data" => data_size = Some(size),
+            _ => {}
+        }
+
+        // Chunks are padded to an even length.
+        at = body + size as usize + (size as usize & 1);
+    }
+
+    let (format_tag, sample_rate, byte_rate) = format.ok_or("Wav has no fmt chunk")?;
+    let data_size = data_size.ok_or("Wav has no data chunk")?;
+
+    // Compressed formats give their length in samples, their byte rate is only an average.
+    match samples {
+        Some(samples) if format_tag != 1 && sample_rate > 0 => {
+            Ok(Seconds(samples as f64 / sample_rate as f64))
+        }
+        _ if byte_rate > 0 => Ok(Seconds(data_size as f64 / byte_rate as f64)),
+        _ => Err("Wav has no byte rate"),
+    }
+}
+
+/// kbps by bitrate index for MPEG-1 layers I, II and III, then MPEG-2 layer I and II/III.
+const BITRATES: [[u32; 15]; 5] = [
+    [
+        0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+    ],
+    [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+    ],
+    [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ],
+    [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+    ],
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+];
+
+/// Bytes and samples in the MPEG audio frame starting at `header`.
+fn mp3_frame(header: &[u8]) -> Option<(usize, u32, u32)> {
+    if header.len() < 4 || header[0] != 0xff || header[1] & 0xe0 != 0xe0 {
+        return None;
+    }
+
+    // 0 is MPEG-2.5, 2 MPEG-2 and 3 MPEG-1. Layers are 3 for I down to 1 for III.
+    let version = (header[1] >> 3) & 0b11;
+    let layer = (header[1] >> 1) & 0b11;
+    let bitrate_index = (header[2] >> 4) as usize;
+    let rate_index = ((header[2] >> 2) & 0b11) as usize;
+    let padding = ((header[2] >> 1) & 1) as usize;
+
+    if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+        return None;
+    }
+
+    let mpeg1 = version == 3;
+    let table = match (mpeg1, layer) {
+        (true, 3) => 0,
+        (true, 2) => 1,
+        (true, _) => 2,
+        (false, 3) => 3,
+        (false, _) => 4,
+    };
+    let bitrate = BITRATES[table][bitrate_index] * 1000;
+    let sample_rate = [44100, 48000, 32000][rate_index]
+        / match version {
+            3 => 1,
+            2 => 2,
+            _ => 4,
+        };
+
+    let (length, samples) = match layer {
+        3 => ((12 * bitrate / sample_rate) as usize * 4 + padding * 4, 384),
+        2 => ((144 * bitrate / sample_rate) as usize + padding, 1152),
+        _ if mpeg1 => ((144 * bitrate / sample_rate) as usize + padding, 1152),
+        _ => ((72 * bitrate / sample_rate) as usize + padding, 576),
+    };
+
+    Some((length, samples, sample_rate))
+}
+
+/// Adds up every frame, so variable bitrate files come out right too.
+pub fn mp3_duration(data: &[u8]) -> Result<Seconds, &'static str> {
+    let mut at = 0;
+
+    // An ID3v2 tag, its size is stored 7 bits to a byte.
+    if data.starts_with(b"ID3") && data.len() >= 10 {
+        let size = data[6..10]
+            .iter()
+            .fold(0usize, |size, &byte| (size << 7) | (byte & 0x7f) as usize);
+        let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+        at = 10 + size + footer;
+    }
+
+    let mut seconds = 0.0;
+    let mut frames = 0;
+
+    while at + 4 <= data.len() {
+        if data[at..].starts_with(b"TAG") {
+            break;
+        }
+
+        match mp3_frame(&data[at..]) {
+            Some((length, samples, sample_rate)) if length > 0 => {
+                seconds += samples as f64 / sample_rate as f64;
+                frames += 1;
+                at += length;
+            }
+            _ => at += 1,
+        }
+    }
+
+    if frames == 0 {
+        return Err("Not an mp3 file");
+    }
+
+    Ok(Seconds(seconds))
+}
+
+/// `PlaySoundStart`s placed so each sound starts as the one before ends.
+///
+/// `sounds` are paths with their lengths, laid out from `start` on `playback`, which is
+/// usually the simulation of the vdm the sounds are added to.
+pub fn back_to_back(sounds: &[(String, Seconds)], start: Tick, playback: &Playback) -> Vec<Action> {
+    let Some(mut time) = playback.time_at(start.0) else {
+        return vec![];
+    };
+
+    let mut actions = vec![];
+
+    for (i, (sound, length)) in sounds.iter().enumerate() {
+        let Some(tick) = playback.tick_at(time) else {
+            break;
+        };
+
+        let mut action = Action::new(ActionType::PlaySoundStart);
+        let props = action.props_mut();
+        props.name = format!("Music {}", i + 1);
+        props.start = Tick(tick).into();
+        props.sound = sound.clone();
+        actions.push(action);
+
+        time += length.0;
+    }
+
+    actions
+}
+
+impl VDM {
+    /// Warns about sounds that are still playing when the next sound starts or playback stops.
+    ///
+    /// Sounds play in real time, so they're timed against the playback simulation.
+    pub fn check_sounds(&self, assets: &Assets, tick_rate: f64) -> Vec<Issue> {
+        let playback = Playback::new(self, tick_rate);
+        let mut issues = vec![];
+        let mut playing: Option<(&str, f64)> = None;
+
+        for fired in &playback.fired {
+            let Action::PlaySoundStart(props) = &self.actions[fired.index] else {
+                continue;
+            };
+
+            if let Some((sound, end)) = playing {
+                if fired.time < end {
+                    issues.push(Issue {
+                        index: fired.index,
+                        severity: Severity::Warning,
+                        message: format!(
+                            "Starts {:.2}s before \"{sound}\" has finished",
+                            end - fired.time
+                        ),
+                    });
+                }
+            }
+
+            // Keep whichever sound goes on longest.
+            if let Ok(length) = assets.sound_duration(&props.sound) {
+                let end = fired.time + length.0;
+                if playing.is_none_or(|(_, playing_end)| end > playing_end) {
+                    playing = Some((&props.sound, end));
+                }
+            }
+        }
+
+        if let (true, Some(last), Some((sound, end))) =
+            (playback.stopped, playback.fired.last(), playing)
+        {
+            if last.time < end {
+                issues.push(Issue {
+                    index: last.index,
+                    severity: Severity::Warning,
+                    message: format!(
+                        "Stops playback {:.2}s before \"{sound}\" has finished",
+                        end - last.time
+                    ),
+                });
+            }
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,201 @@
+use crate::action::{Action, ActionType, TextEffect};
+use crate::colour::Rgba;
+use crate::time::Seconds;
+use crate::VDM;
+use std::{fmt::Write, fs, path::Path};
+
+/// A single caption, timed in seconds from the start of the demo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// How imported captions look in game.
+#[derive(Debug, Clone)]
+pub struct TextStyle {
+    pub font: String,
+    /// Normalized screen position, -1 centers on that axis.
+    pub xy: [f64; 2],
+    pub rgba1: Rgba,
+    pub rgba2: Rgba,
+    pub effect: TextEffect,
+    pub fade_in: f64,
+    pub fade_out: f64,
+    pub fx_time: f64,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        TextStyle {
+            font: "".to_string(),
+            xy: [-1.0, 0.85],
+            rgba1: Rgba::WHITE,
+            rgba2: Rgba::WHITE,
+            effect: TextEffect::FadeInOut,
+            fade_in: 0.25,
+            fade_out: 0.25,
+            fx_time: 0.25,
+        }
+    }
+}
+
+pub fn open(file_path: impl AsRef<Path>) -> Result<Vec<Cue>, &'static str> {
+    let file_path = file_path.as_ref();
+
+    if file_path
+        .extension()
+        .is_some_and(|ext| ext == "srt" || ext == "vtt")
+    {
+        let Ok(file) = fs::read_to_string(file_path) else {
+            return Err("Error Opening File.");
+        };
+
+        return Ok(parse(file));
+    }
+
+    Err("Invalid file type")
+}
+
+/// Parses SRT or WebVTT cues, blocks without a timing line are ignored.
+pub fn parse(text: impl AsRef<str>) -> Vec<Cue> {
+    let text = text.as_ref().replace("\r\n", "\n");
+    let mut cues = vec![];
+
+    for block in text.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+
+        let mut times = timing.split("-->");
+        let (Some(start), Some(end)) = (
+            times.next().and_then(parse_timestamp),
+            times.next().and_then(parse_timestamp),
+        ) else {
+            continue;
+        };
+
+        let text = lines.map(strip_tags).collect::<Vec<_>>().join("\n");
+
+        cues.push(Cue { start, end, text });
+    }
+
+    cues
+}
+
+/// Parses `hh:mm:ss,mmm`, `hh:mm:ss.mmm` or `mm:ss.mmm`, ignoring any trailing cue settings.
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let timestamp = timestamp.split_whitespace().next()?.replace(',', ".");
+
+    timestamp.split(':').try_fold(0.0, |total, part| {
+        Some(total * 60.0 + part.parse::<f64>().ok()?)
+    })
+}
+
+fn strip_tags(line: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// Creates a `TextMessageStart` for every cue.
+///
+/// The engine shows a message for `fadein + holdtime + fadeout`, so the hold time is
+/// whatever is left of the cue once the fades are taken out.
+pub fn to_actions(cues: &[Cue], style: &TextStyle, tick_rate: f64) -> Vec<Action> {
+    cues.iter()
+        .enumerate()
+        .map(|(i, cue)| {
+            let mut action = Action::new(ActionType::TextMessageStart);
+            let props = action.props_mut();
+
+            props.name = format!("Subtitle {}", i + 1);
+            props.start = Seconds(cue.start).to_ticks(tick_rate).into();
+            // Neither quotes nor line breaks survive a vdm value.
+            props.message = cue.text.replace('"', "'").replace('\n', " ");
+            props.font = style.font.clone();
+            props.fade_in = style.fade_in;
+            props.fade_out = style.fade_out;
+            props.hold_time = (cue.end - cue.start - style.fade_in - style.fade_out).max(0.0);
+            props.fx_time = style.fx_time;
+            props.effect = style.effect;
+            props.xy = style.xy;
+            props.rgba1 = style.rgba1;
+            props.rgba2 = style.rgba2;
+
+            action
+        })
+        .collect()
+}
+
+/// Collects the `TextMessageStart` actions of a vdm as cues, sorted by start time.
+pub fn from_vdm(vdm: &VDM, tick_rate: f64) -> Vec<Cue> {
+    let mut cues = vdm
+        .actions
+        .iter()
+        .filter_map(|action| {
+            let Action::TextMessageStart(props) = action else {
+                return None;
+            };
+
+            let start = props.start.seconds(tick_rate).map_or(0.0, |start| start.0);
+
+            Some(Cue {
+                start,
+                end: start + props.fade_in + props.hold_time + props.fade_out,
+                text: props.message.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    cues
+}
+
+pub fn to_srt(cues: &[Cue]) -> String {
+    let mut srt = String::new();
+
+    for (i, cue) in cues.iter().enumerate() {
+        let _ = write!(
+            srt,
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            format_timestamp(cue.start),
+            format_timestamp(cue.end),
+            cue.text
+        );
+    }
+
+    srt
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
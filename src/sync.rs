@@ -0,0 +1,140 @@
+use crate::demo::Demo;
+use crate::generator::{Clip, Generator};
+use crate::VDM;
+
+/// A demo placed on the shared server tick timeline.
+#[derive(Debug, Clone)]
+pub struct SyncedDemo {
+    pub name: String,
+    /// The server tick at demo tick 0.
+    pub offset: i64,
+    /// Length of the demo in ticks, when known.
+    pub ticks: Option<i64>,
+}
+
+/// Several demos of the same match, lined up so a moment in one can be found in the others.
+#[derive(Debug, Clone, Default)]
+pub struct DemoSync {
+    pub demos: Vec<SyncedDemo>,
+}
+
+impl DemoSync {
+    pub fn new() -> Self {
+        DemoSync { demos: vec![] }
+    }
+
+    /// Adds a demo using the server tick read from its packets.
+    pub fn add_demo(&mut self, demo: &Demo) -> Result<usize, &'static str> {
+        let Some(offset) = demo.server_tick_offset else {
+            return Err("Demo has no server tick");
+        };
+
+        self.demos.push(SyncedDemo {
+            name: demo.name.clone(),
+            offset,
+            ticks: Some(demo.header.ticks as i64),
+        });
+
+        Ok(self.demos.len() - 1)
+    }
+
+    /// Adds a demo by matching the ticks of events seen in both it and an already added demo.
+    ///
+    /// `anchor_ticks[i]` and `ticks[i]` must be the same event, the median difference is used.
+    pub fn add_by_events(
+        &mut self,
+        name: impl Into<String>,
+        anchor: usize,
+        anchor_ticks: &[i64],
+        ticks: &[i64],
+    ) -> Result<usize, &'static str> {
+        let Some(anchor) = self.demos.get(anchor) else {
+            return Err("Invalid anchor demo");
+        };
+
+        let Some(difference) = event_offset(anchor_ticks, ticks) else {
+            return Err("No shared events");
+        };
+
+        self.demos.push(SyncedDemo {
+            name: name.into(),
+            offset: anchor.offset - difference,
+            ticks: None,
+        });
+
+        Ok(self.demos.len() - 1)
+    }
+
+    /// Maps a tick in demo `from` to the same moment in demo `to`.
+    pub fn map_tick(&self, from: usize, to: usize, tick: i64) -> i64 {
+        tick + self.demos[from].offset - self.demos[to].offset
+    }
+
+    /// Maps a tick range in demo `from` onto every demo.
+    ///
+    /// Demos that were not recording during the range get `None`, ranges that are
+    /// only partially covered are clamped to the demo.
+    pub fn map_range(
+        &self,
+        from: usize,
+        start_tick: i64,
+        stop_tick: i64,
+    ) -> Vec<Option<(i64, i64)>> {
+        (0..self.demos.len())
+            .map(|to| {
+                let start = self.map_tick(from, to, start_tick).max(0);
+                let mut stop = self.map_tick(from, to, stop_tick);
+
+                if let Some(ticks) = self.demos[to].ticks {
+                    stop = stop.min(ticks);
+                }
+
+                (start < stop).then_some((start, stop))
+            })
+            .collect()
+    }
+
+    /// Builds a vdm for every demo that records the same moments as `clips` in demo `from`.
+    pub fn vdms(&self, from: usize, clips: &[Clip], generator: &Generator) -> Vec<VDM> {
+        let mut per_demo = vec![vec![]; self.demos.len()];
+
+        for clip in clips {
+            let ranges = self.map_range(from, clip.start_tick, clip.stop_tick);
+
+            for (to, range) in ranges.into_iter().enumerate() {
+                if let Some((start_tick, stop_tick)) = range {
+                    let mut clip = clip.clone();
+                    clip.start_tick = start_tick;
+                    clip.stop_tick = stop_tick;
+                    per_demo[to].push(clip);
+                }
+            }
+        }
+
+        per_demo
+            .iter()
+            .zip(&self.demos)
+            .map(|(clips, demo)| {
+                let mut vdm = generator.compile(clips);
+                vdm.name = demo.name.clone();
+                vdm
+            })
+            .collect()
+    }
+}
+
+/// The median of `ticks[i] - anchor_ticks[i]`.
+pub fn event_offset(anchor_ticks: &[i64], ticks: &[i64]) -> Option<i64> {
+    let mut differences = anchor_ticks
+        .iter()
+        .zip(ticks)
+        .map(|(anchor, tick)| tick - anchor)
+        .collect::<Vec<_>>();
+
+    if differences.is_empty() {
+        return None;
+    }
+
+    differences.sort();
+    Some(differences[differences.len() / 2])
+}
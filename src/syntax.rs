@@ -0,0 +1,415 @@
+use crate::action::{Action, ActionType};
+use crate::validate::Severity;
+use crate::VDM;
+
+/// A byte range in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn contains(&self, offset: usize) -> bool {
+        (self.start..=self.end).contains(&offset)
+    }
+}
+
+/// A `key "value"` line inside an action.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub key: String,
+    pub key_span: Span,
+    pub value: String,
+    pub value_span: Span,
+}
+
+/// A numbered action block such as `"1" { ... }`.
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub key: String,
+    pub key_span: Span,
+    /// From the key to the closing brace.
+    pub span: Span,
+    pub entries: Vec<Entry>,
+}
+
+impl Block {
+    pub fn entry(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.key == key)
+    }
+
+    pub fn action_type(&self) -> Option<ActionType> {
+        ActionType::from_factory(&self.entry("factory")?.value)
+    }
+
+    /// Whether the demo player ignores this entry, it uses the tick when a tick and a time are both set.
+    pub fn shadowed(&self, entry: &Entry) -> bool {
+        match entry.key.strip_suffix("time") {
+            Some(point @ ("start" | "stop" | "skipto")) => {
+                self.entry(&format!("{point}tick")).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the action's factory has this key, others are dropped when the action is written.
+    pub fn is_used(&self, entry: &Entry) -> bool {
+        entry.key == "factory"
+            || self
+                .action_type()
+                .is_some_and(|action_type| action_type.has_key(&entry.key))
+    }
+
+    pub fn to_action(&self) -> Result<Action, &'static str> {
+        let Some(factory) = self.entry("factory") else {
+            return Err("Missing factory");
+        };
+
+        let Some(action_type) = ActionType::from_factory(&factory.value) else {
+            return Err("Unknown factory");
+        };
+
+        let mut action = Action::new(action_type);
+        let props = action.props_mut();
+
+        for entry in &self.entries {
+            if self.shadowed(entry) {
+                continue;
+            }
+
+            props.set(&entry.key, &entry.value)?;
+        }
+        props.key = Some(self.key.clone());
+
+        Ok(action)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SyntaxError {
+    pub message: &'static str,
+    pub span: Span,
+}
+
+/// A vdm as written, with the position of everything in it.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    pub blocks: Vec<Block>,
+    pub errors: Vec<SyntaxError>,
+    /// `//` comments, which aren't kept when the vdm is written back.
+    pub comments: Vec<Span>,
+}
+
+/// A problem found in a document, positioned in the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Document {
+    /// The actions of every block that parses, in order.
+    pub fn to_vdm(&self) -> VDM {
+        let mut vdm = VDM::new();
+
+        for block in &self.blocks {
+            if let Ok(action) = block.to_action() {
+                vdm.add(action);
+            }
+        }
+
+        vdm
+    }
+
+    /// Every action, failing on the first syntax error or block that isn't a valid action
+    /// instead of leaving it out.
+    pub fn try_to_vdm(&self) -> Result<VDM, &'static str> {
+        if let Some(error) = self.errors.first() {
+            return Err(error.message);
+        }
+
+        let mut vdm = VDM::new();
+
+        for block in &self.blocks {
+            vdm.add(block.to_action()?);
+        }
+
+        Ok(vdm)
+    }
+
+    /// Syntax errors, unknown keys and invalid values, followed by everything `VDM::validate` finds.
+    pub fn diagnostics(&self, tick_rate: f64) -> Vec<Diagnostic> {
+        let mut diagnostics = self
+            .errors
+            .iter()
+            .map(|error| Diagnostic {
+                span: error.span,
+                severity: Severity::Error,
+                message: error.message.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        // Validator issues are indexed by action, so keep track of which block made each one.
+        let mut action_blocks = vec![];
+
+        for block in &self.blocks {
+            let Some(action_type) = block.action_type() else {
+                diagnostics.push(Diagnostic {
+                    span: block
+                        .entry("factory")
+                        .map_or(block.key_span, |e| e.value_span),
+                    severity: Severity::Error,
+                    message: "Missing or unknown factory".to_string(),
+                });
+                continue;
+            };
+
+            for entry in &block.entries {
+                // The value isn't read, so whether it's valid doesn't matter.
+                if block.shadowed(entry) {
+                    diagnostics.push(Diagnostic {
+                        span: entry.key_span,
+                        severity: Severity::Warning,
+                        message: format!("{} is ignored as the tick is also set", entry.key),
+                    });
+                    continue;
+                }
+
+                if !block.is_used(entry) {
+                    diagnostics.push(Diagnostic {
+                        span: entry.key_span,
+                        severity: Severity::Warning,
+                        message: format!("{} is not used by {}", entry.key, action_type.factory()),
+                    });
+                }
+
+                if let Err(message) = Action::new(action_type)
+                    .props_mut()
+                    .set(&entry.key, &entry.value)
+                {
+                    diagnostics.push(Diagnostic {
+                        span: entry.value_span,
+                        severity: Severity::Error,
+                        message: message.to_string(),
+                    });
+                }
+            }
+
+            // The same check `to_vdm` makes, so the indices line up.
+            if block.to_action().is_ok() {
+                action_blocks.push(block);
+            }
+        }
+
+        for issue in self.to_vdm().validate(tick_rate) {
+            diagnostics.push(Diagnostic {
+                span: action_blocks[issue.index].key_span,
+                severity: issue.severity,
+                message: issue.message,
+            });
+        }
+
+        diagnostics
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Open,
+    Close,
+    Text(String),
+}
+
+/// Parses the keyvalues structure of a vdm, recovering from errors where it can.
+pub fn parse(text: &str) -> Document {
+    let mut document = Document::default();
+    let tokens = tokenize(text, &mut document.errors, &mut document.comments);
+    let mut tokens = tokens.into_iter().peekable();
+
+    match tokens.next() {
+        Some((Token::Text(root), _)) if root.eq_ignore_ascii_case("demoactions") => {}
+        Some((_, span)) => {
+            document.errors.push(SyntaxError {
+                message: "Expected demoactions",
+                span,
+            });
+            return document;
+        }
+        None => return document,
+    }
+
+    match tokens.next() {
+        Some((Token::Open, _)) => {}
+        token => {
+            document.errors.push(SyntaxError {
+                message: "Expected {",
+                span: token.map(|(_, span)| span).unwrap_or(Span {
+                    start: text.len(),
+                    end: text.len(),
+                }),
+            });
+            return document;
+        }
+    }
+
+    loop {
+        let Some((token, key_span)) = tokens.next() else {
+            document.errors.push(SyntaxError {
+                message: "Missing closing }",
+                span: Span {
+                    start: text.len(),
+                    end: text.len(),
+                },
+            });
+            break;
+        };
+
+        let key = match token {
+            Token::Close => break,
+            Token::Open => {
+                document.errors.push(SyntaxError {
+                    message: "Expected an action key",
+                    span: key_span,
+                });
+                continue;
+            }
+            Token::Text(key) => key,
+        };
+
+        if !matches!(tokens.peek(), Some((Token::Open, _))) {
+            document.errors.push(SyntaxError {
+                message: "Expected {",
+                span: key_span,
+            });
+            continue;
+        }
+        tokens.next();
+
+        let mut block = Block {
+            key,
+            key_span,
+            span: key_span,
+            entries: vec![],
+        };
+
+        loop {
+            match tokens.next() {
+                Some((Token::Close, span)) => {
+                    block.span.end = span.end;
+                    break;
+                }
+                Some((Token::Text(key), key_span)) => match tokens.peek() {
+                    Some((Token::Text(_), _)) => {
+                        let Some((Token::Text(value), value_span)) = tokens.next() else {
+                            unreachable!();
+                        };
+                        block.entries.push(Entry {
+                            key,
+                            key_span,
+                            value,
+                            value_span,
+                        });
+                    }
+                    _ => document.errors.push(SyntaxError {
+                        message: "Expected a value",
+                        span: key_span,
+                    }),
+                },
+                Some((Token::Open, span)) => document.errors.push(SyntaxError {
+                    message: "Unexpected {",
+                    span,
+                }),
+                None => {
+                    document.errors.push(SyntaxError {
+                        message: "Missing closing }",
+                        span: block.key_span,
+                    });
+                    block.span.end = text.len();
+                    document.blocks.push(block);
+                    return document;
+                }
+            }
+        }
+
+        document.blocks.push(block);
+    }
+
+    if let Some((_, span)) = tokens.next() {
+        document.errors.push(SyntaxError {
+            message: "Unexpected text after demoactions",
+            span,
+        });
+    }
+
+    document
+}
+
+/// Splits keyvalues text into tokens, scheme files are read with this too.
+pub(crate) fn tokenize(
+    text: &str,
+    errors: &mut Vec<SyntaxError>,
+    comments: &mut Vec<Span>,
+) -> Vec<(Token, Span)> {
+    let bytes = text.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+
+        match bytes[i] {
+            b if b.is_ascii_whitespace() => i += 1,
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+
+                comments.push(Span { start, end: i });
+            }
+            b'{' => {
+                i += 1;
+                tokens.push((Token::Open, Span { start, end: i }));
+            }
+            b'}' => {
+                i += 1;
+                tokens.push((Token::Close, Span { start, end: i }));
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' && bytes[i] != b'\n' {
+                    i += 1;
+                }
+
+                let value = text[start + 1..i].to_string();
+
+                if bytes.get(i) == Some(&b'"') {
+                    i += 1;
+                } else {
+                    errors.push(SyntaxError {
+                        message: "Unterminated string",
+                        span: Span { start, end: i },
+                    });
+                }
+
+                tokens.push((Token::Text(value), Span { start, end: i }));
+            }
+            _ => {
+                while i < bytes.len()
+                    && !bytes[i].is_ascii_whitespace()
+                    && !matches!(bytes[i], b'{' | b'}' | b'"')
+                {
+                    i += 1;
+                }
+
+                tokens.push((
+                    Token::Text(text[start..i].to_string()),
+                    Span { start, end: i },
+                ));
+            }
+        }
+    }
+
+    tokens
+}
@@ -0,0 +1,118 @@
+use crate::action::{Action, ActionType};
+use crate::assets::Assets;
+use crate::vpk::crc32;
+use crate::VDM;
+use std::fs;
+
+/// A v1 vpk holding `(extension, directory, name, data)` files, all preloaded.
+fn vpk(files: &[(&str, &str, &str, &[u8])]) -> Vec<u8> {
+    let mut tree = vec![];
+    for (extension, dir, name, data) in files {
+        for part in [extension, dir, name] {
+            tree.extend(part.as_bytes());
+            tree.push(0);
+        }
+        // CRC, preload size, archive, offset, length, terminator.
+        tree.extend(crc32(data).to_le_bytes());
+        tree.extend((data.len() as u16).to_le_bytes());
+        tree.extend([0xff, 0x7f]);
+        tree.extend([0; 8]);
+        tree.extend([0xff, 0xff]);
+        tree.extend(*data);
+        tree.extend([0, 0]);
+    }
+    tree.push(0);
+
+    let mut data = vec![];
+    data.extend(0x55aa_1234u32.to_le_bytes());
+    data.extend(1u32.to_le_bytes());
+    data.extend((tree.len() as u32).to_le_bytes());
+    data.extend(tree);
+    data
+}
+
+#[test]
+fn flags_unknown_fonts_and_sounds() {
+    let game = std::env::temp_dir().join(format!("vdm-assets-{}", std::process::id()));
+    fs::create_dir_all(game.join("resource")).unwrap();
+    fs::create_dir_all(game.join("custom/hud/sound/ui")).unwrap();
+    fs::write(
+        game.join("resource/ClientScheme.res"),
+        "#base \"fonts.res\"\n\"Scheme\"\n{\n\t\"Fonts\"\n\t{\n\t\t\"HudFontBig\" [$WIN32]\n\t\t{\n\t\t\t\"1\" { \"name\" \"TF2\" }\n\t\t}\n\t}\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        game.join("resource/fonts.res"),
+        "Scheme { Fonts { // comment\n DefaultSmall { } } }",
+    )
+    .unwrap();
+    fs::write(game.join("custom/hud/sound/ui/hitsound.wav"), []).unwrap();
+    fs::write(
+        game.join("tf2_sound_misc_dir.vpk"),
+        vpk(&[("wav", "sound/music", "victory", b"")]),
+    )
+    .unwrap();
+    fs::write(
+        game.join("tf2_misc_dir.vpk"),
+        vpk(&[
+            (
+                "res",
+                "resource",
+                "clientscheme",
+                b"#base \"sourcescheme.res\"\nScheme { Fonts { HudFontSmall { } } }",
+            ),
+            (
+                "res",
+                "resource",
+                "sourcescheme",
+                b"Scheme { Fonts { DebugFixed { } } }",
+            ),
+        ]),
+    )
+    .unwrap();
+    fs::write(game.join("broken_dir.vpk"), b"not a vpk").unwrap();
+
+    let assets = Assets::scan(&game).unwrap();
+    fs::remove_dir_all(&game).unwrap();
+
+    assert!(assets.has_font("hudfontbig") && assets.has_font("DefaultSmall"));
+    assert!(assets.has_font("HudFontSmall") && assets.has_font("DebugFixed"));
+    assert_eq!(
+        assets.unreadable,
+        [(game.join("broken_dir.vpk"), "Not a vpk")]
+    );
+    assert!(assets.has_sound(")music/victory.wav"));
+    assert!(assets.has_sound("sound\\ui\\hitsound.wav"));
+
+    let mut vdm = VDM::new();
+    for (action_type, key, value) in [
+        (ActionType::TextMessageStart, "font", "HudFontBgi"),
+        (ActionType::TextMessageStart, "font", "HudFontBig"),
+        (ActionType::PlaySoundStart, "sound", "music/victroy.wav"),
+        (ActionType::PlaySoundStart, "sound", "nothing/like/it.wav"),
+        (ActionType::PlaySoundStart, "sound", "!HG_ALERT1"),
+    ] {
+        let mut action = Action::new(action_type);
+        action.props_mut().set(key, value).unwrap();
+        vdm.add(action);
+    }
+
+    let issues = vdm.check_assets(&assets);
+    assert_eq!(
+        issues
+            .iter()
+            .map(|issue| (issue.index, issue.message.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (
+                0,
+                "Unknown font \"HudFontBgi\", did you mean \"HudFontBig\"?"
+            ),
+            (
+                2,
+                "Unknown sound \"music/victroy.wav\", did you mean \"music/victory.wav\"?"
+            ),
+            (3, "Unknown sound \"nothing/like/it.wav\""),
+        ]
+    );
+}
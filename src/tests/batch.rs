@@ -0,0 +1,105 @@
+use crate::action::ActionType;
+use crate::batch::{Batch, Operation};
+use crate::time::{Tick, TimePoint};
+use crate::VDM;
+use std::fs;
+
+#[test]
+fn works_through_a_directory() {
+    let root = std::env::temp_dir().join(format!("vdm-batch-{}", std::process::id()));
+    fs::create_dir_all(root.join("week1")).unwrap();
+
+    let mut vdm = VDM::new();
+    let props = vdm.create_action(ActionType::PlayCommands).props_mut();
+    props.name = "Record".to_string();
+    props.start = Tick(100).into();
+    props.commands = "startmovie a".to_string();
+    vdm.create_action(ActionType::SkipAhead).props_mut().start = Tick(200).into();
+    let props = vdm
+        .create_action(ActionType::ChangePlaybackRate)
+        .props_mut();
+    props.start = Tick(300).into();
+    props.stop = Tick(400).into();
+    props.playback_rate = 0.5;
+
+    fs::write(root.join("a.dem"), []).unwrap();
+    vdm.export(root.join("a.vdm")).unwrap();
+    fs::write(root.join("week1/b.dem"), []).unwrap();
+    fs::write(root.join("week1/c.vdm"), "demoactions\r\n{\r\n\t\"7\"\r\n\t{\r\n\t\tfactory \"Pause\"\r\n\t\tname \"Wait\"\r\n\t\tstarttick \"5\"\r\n\t\tduration \"1.000\"\r\n\t}\r\n}\r\n").unwrap();
+    let unknown_key = "demoactions\r\n{\r\n\t\"1\"\r\n\t{\r\n\t\tfactory \"Pause\"\r\n\t\tcomment \"keep me\"\r\n\t\tstarttick \"5\"\r\n\t}\r\n}\r\n";
+    fs::write(root.join("week1/d.vdm"), unknown_key).unwrap();
+    let commented = "demoactions\r\n{\r\n\t// Kept for the intro\r\n\t\"1\"\r\n\t{\r\n\t\tfactory \"Pause\"\r\n\t\tstarttick \"5\"\r\n\t}\r\n}\r\n";
+    fs::write(root.join("week1/e.vdm"), commented).unwrap();
+
+    // A link back to the root isn't followed.
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&root, root.join("week1/loop")).unwrap();
+
+    let batch = Batch::discover(&root).unwrap();
+    assert_eq!(batch.pairs.len(), 5);
+    assert_eq!(
+        batch.demos_without_vdm().collect::<Vec<_>>(),
+        [root.join("week1/b.dem")]
+    );
+
+    let report = batch.apply(Operation::Validate);
+    assert_eq!(report.written(), 0);
+    assert_eq!(
+        report.files[0].notes,
+        ["error in \"Unnamed\": Has no skiptotick or skiptotime"]
+    );
+
+    let report = batch.apply(Operation::Shift(Tick(10)));
+    assert_eq!(report.written(), 2);
+    let rate = VDM::open(root.join("a.vdm")).unwrap().last().props();
+    assert_eq!(
+        (rate.start, rate.stop),
+        (TimePoint::Tick(Tick(310)), TimePoint::Tick(Tick(410)))
+    );
+    // Files that would lose something aren't touched.
+    assert_eq!(
+        report.files[2].error,
+        Some("Has keys that would be lost by saving")
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("week1/d.vdm")).unwrap(),
+        unknown_key
+    );
+    assert_eq!(
+        report.files[3].error,
+        Some("Has comments that would be lost by saving")
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("week1/e.vdm")).unwrap(),
+        commented
+    );
+    let shifted = VDM::open(root.join("week1/c.vdm")).unwrap();
+    assert_eq!(shifted.first().props().start, TimePoint::Tick(Tick(15)));
+    // The original keys are kept.
+    assert!(fs::read_to_string(root.join("week1/c.vdm"))
+        .unwrap()
+        .contains("\"7\""));
+
+    let report = batch.apply(Operation::Regenerate);
+    assert_eq!(report.written(), 1);
+    assert_eq!(batch.apply(Operation::Regenerate).written(), 0);
+
+    // Errors are collected per file, the rest still run.
+    let report = batch.run(|pair, vdm| match pair.demo {
+        Some(_) => Err("Demo is in use"),
+        None => {
+            vdm.clear();
+            Ok(vec![])
+        }
+    });
+    assert_eq!(report.failed().count(), 3);
+    assert_eq!(report.written(), 1);
+    let summary = report.to_string();
+    assert!(summary.contains("a.vdm: error: Demo is in use\n"));
+    assert!(summary.ends_with("4 vdms, 1 written, 3 failed\n"));
+
+    let report = batch.apply(Operation::StripCommands);
+    assert_eq!(report.files[0].notes, ["Removed 1 PlayCommands"]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
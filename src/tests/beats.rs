@@ -0,0 +1,53 @@
+use crate::action::Action;
+use crate::beats::{self, BeatClip, BeatSync, Beats};
+use crate::generator::Clip;
+use crate::ramp::SpeedRamp;
+use crate::time::{Seconds, Tick};
+
+#[test]
+fn reads_beat_lists() {
+    let beats = Beats::parse("time,strength\n# comment\n1.5,0.8\n0.5;1\n\n  2.25\n");
+    assert_eq!(beats.times, [0.5, 1.5, 2.25]);
+
+    let beats = Beats::from_bpm(120.0, Seconds(0.25), Seconds(2.0));
+    assert_eq!(beats.times, [0.25, 0.75, 1.25, 1.75]);
+    assert!(Beats::from_bpm(120.0, Seconds(3.0), Seconds(2.0))
+        .times
+        .is_empty());
+
+    assert_eq!(beats.nearest(1.1, 0.0), Some(2));
+    assert_eq!(beats.nearest(0.3, 0.75), Some(2));
+    assert_eq!(beats.nearest(1.0, 2.0), None);
+}
+
+#[test]
+fn cuts_on_beats() {
+    let sync = BeatSync::new(Beats::from_bpm(120.0, Seconds(0.0), Seconds(60.0)));
+
+    let mut slowed = BeatClip::new(Clip::new("slowed", 5000, 5200));
+    slowed
+        .ramps
+        .push(SpeedRamp::new(Tick(5050), Tick(5100), 0.5, 0.5));
+
+    let (vdm, timings) = sync.compile(&[BeatClip::new(Clip::new("plain", 1000, 1300)), slowed]);
+
+    // 300 ticks is just over 4.5 seconds.
+    assert_eq!((timings[0].stop_tick, timings[0].end), (1297, 4.5));
+    assert_eq!(timings[0].end_beat, Some(9));
+
+    // The ramp moves to a second into the clip, and the half speed stretches the clip to 4s.
+    assert_eq!(timings[1].ramp_beats, [Some(11)]);
+    assert_eq!((timings[1].start, timings[1].end), (4.5, 8.5));
+    assert_eq!(timings[1].stop_tick, 5214);
+
+    let ramp = vdm
+        .actions
+        .iter()
+        .find(|action| matches!(action, Action::ChangePlaybackRate(_)))
+        .unwrap();
+    assert_eq!(ramp.props().start, Tick(5066).into());
+
+    let report = beats::report(&timings);
+    assert_eq!(report.lines().count(), 3);
+    assert!(report.lines().nth(2).unwrap().ends_with("(+14) slowed"));
+}
@@ -0,0 +1,43 @@
+use crate::action::{Action, FadeFlags};
+use crate::colour::Rgba;
+use crate::VDM;
+
+#[test]
+fn parses_hex_names_and_tuples() {
+    assert_eq!("#f80".parse(), Ok(Rgba::rgb(255, 136, 0)));
+    assert_eq!("ff880080".parse(), Ok(Rgba::new(255, 136, 0, 128)));
+    assert_eq!("Orange".parse(), Ok(Rgba::rgb(255, 165, 0)));
+    assert_eq!("grey".parse(), Ok(Rgba::rgb(128, 128, 128)));
+    assert_eq!("10, 20, 30".parse(), Ok(Rgba::rgb(10, 20, 30)));
+    assert_eq!(
+        "rgba(10, 20, 30, 40)".parse(),
+        Ok(Rgba::new(10, 20, 30, 40))
+    );
+    assert!("#12345".parse::<Rgba>().is_err());
+    assert!("(1, 2)".parse::<Rgba>().is_err());
+    assert!("1, 2, 300".parse::<Rgba>().is_err());
+
+    let colour = Rgba::new(1, 2, 3, 4);
+    assert_eq!(colour.to_hex(), "#01020304");
+    assert_eq!(Rgba::from_hex(&colour.to_hex()), Ok(colour));
+    assert_eq!(<[u8; 4]>::from(colour), [1, 2, 3, 4]);
+    assert_eq!(Rgba::from((1, 2, 3)), Rgba::rgb(1, 2, 3));
+}
+
+#[test]
+fn fades_keep_flags_and_colour() {
+    let text = "demoactions\r\n{\r\n\t\"1\"\r\n\t{\r\n\t\tfactory \"ScreenFadeStart\"\r\n\t\tname \"Fade\"\r\n\t\tstarttick \"10\"\r\n\t\tduration \"1.000\"\r\n\t\tholdtime \"0.000\"\r\n\t\tFFADE_OUT \"1\"\r\n\t\tFFADE_STAYOUT \"1\"\r\n\t\tr \"255\"\r\n\t\tg \"128\"\r\n\t\tb \"0\"\r\n\t\ta \"200\"\r\n\t}\r\n}\r\n";
+    let vdm = VDM::from(text);
+
+    let props = vdm.actions[0].props();
+    assert_eq!(props.fade_flags, FadeFlags::OUT | FadeFlags::STAYOUT);
+    assert_eq!(props.rgba1, Rgba::new(255, 128, 0, 200));
+    assert_eq!(props.get("FFADE_STAYOUT"), Some("1".to_string()));
+    assert_eq!(props.get("FFADE_IN"), None);
+    assert_eq!(vdm.to_string(), text);
+
+    let mut action = Action::new(crate::action::ActionType::ScreenFadeStart);
+    action.props_mut().set("FFADE_PURGE", "1").unwrap();
+    action.props_mut().set("FFADE_PURGE", "0").unwrap();
+    assert!(action.props().fade_flags.is_empty());
+}
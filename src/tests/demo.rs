@@ -0,0 +1,347 @@
+use crate::demo::{account_id, Demo};
+use crate::generator::{Bookmark, Clip, Generator};
+use crate::time::{Tick, TimePoint};
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    fn bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 1 << (self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    fn bits(&mut self, value: u32, count: u32) {
+        for i in 0..count {
+            self.bit(value >> i & 1 == 1);
+        }
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.bits(*byte as u32, 8);
+        }
+    }
+
+    fn string(&mut self, string: &str) {
+        self.bytes(string.as_bytes());
+        self.bits(0, 8);
+    }
+
+    fn append(&mut self, other: &BitWriter) {
+        for i in 0..other.len {
+            self.bit(other.bytes[i / 8] >> (i % 8) & 1 == 1);
+        }
+    }
+}
+
+/// Literals, with runs of zeros written as copies so both kinds of snappy element are used.
+fn snappy(data: &[u8]) -> Vec<u8> {
+    fn literal(out: &mut Vec<u8>, bytes: &[u8]) {
+        match bytes.len() {
+            0 => return,
+            1..=60 => out.push(((bytes.len() - 1) << 2) as u8),
+            len => out.extend([60 << 2, (len - 1) as u8]),
+        }
+        out.extend(bytes);
+    }
+
+    let mut out = vec![data.len() as u8 | 0x80, (data.len() >> 7) as u8];
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < data.len() {
+        let run = data[i..]
+            .iter()
+            .take_while(|&&byte| byte == 0)
+            .count()
+            .min(60);
+        if run < 6 {
+            i += 1;
+            continue;
+        }
+
+        literal(&mut out, &data[start..=i]);
+        out.extend([((run - 2) << 2) as u8 | 2, 1, 0]);
+        i += run;
+        start = i;
+    }
+
+    literal(&mut out, &data[start..]);
+    out
+}
+
+fn player_info(name: &str, user_id: i32, steam_id: &str, friends_id: u32) -> Vec<u8> {
+    let mut data = vec![0; 132];
+    data[..name.len()].copy_from_slice(name.as_bytes());
+    data[32..36].copy_from_slice(&user_id.to_le_bytes());
+    data[36..36 + steam_id.len()].copy_from_slice(steam_id.as_bytes());
+    data[72..76].copy_from_slice(&friends_id.to_le_bytes());
+    data
+}
+
+fn string_tables(players: &[Vec<u8>]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    writer.bits(2, 8);
+
+    writer.string("downloadables");
+    writer.bits(1, 16);
+    writer.string("maps/cp_process_final.bsp");
+    writer.bit(false);
+    writer.bit(false);
+
+    writer.string("userinfo");
+    writer.bits(players.len() as u32, 16);
+    for (i, player) in players.iter().enumerate() {
+        writer.string(&i.to_string());
+        writer.bit(true);
+        writer.bits(player.len() as u32, 16);
+        writer.bytes(player);
+    }
+    writer.bit(false);
+
+    writer.bytes
+}
+
+/// `userinfo` entries, each an explicit index or the next one, with a key and player info.
+fn user_info_entries(entries: &[(Option<u32>, Option<&str>, Vec<u8>)]) -> BitWriter {
+    let mut writer = BitWriter::default();
+
+    for (index, key, data) in entries {
+        writer.bit(index.is_none());
+        if let Some(index) = index {
+            writer.bits(*index, 8);
+        }
+
+        writer.bit(key.is_some());
+        if let Some(key) = key {
+            writer.bit(false);
+            writer.string(key);
+        }
+
+        writer.bit(true);
+        writer.bits(data.len() as u32, 14);
+        writer.bytes(data);
+    }
+
+    writer
+}
+
+fn create_table(
+    packet: &mut BitWriter,
+    name: &str,
+    count: u32,
+    data: &BitWriter,
+    compressed: bool,
+) {
+    packet.bits(12, 6);
+    packet.string(name);
+    packet.bits(256, 16);
+    packet.bits(count, 9);
+
+    let mut length = data.len as u32;
+    while length >= 0x80 {
+        packet.bits(length & 0x7f | 0x80, 8);
+        length >>= 7;
+    }
+    packet.bits(length, 8);
+
+    packet.bit(false);
+    packet.bit(compressed);
+    packet.append(data);
+}
+
+fn packet_frame(bytes: &mut Vec<u8>, command: u8, tick: i32, packet: &BitWriter) {
+    bytes.push(command);
+    bytes.extend(tick.to_le_bytes());
+    bytes.extend([0; 84]);
+    bytes.extend((packet.bytes.len() as i32).to_le_bytes());
+    bytes.extend(&packet.bytes);
+}
+
+/// A demo with `userinfo` sent in its packets: a player in a compressed table at signon,
+/// then renamed and joined by another at tick 2000.
+fn late_join_demo() -> Vec<u8> {
+    let mut bytes = demo_bytes(1001);
+    bytes.truncate(1072);
+
+    let mut signon = BitWriter::default();
+    signon.bits(7, 6);
+    signon.string("hello");
+    create_table(
+        &mut signon,
+        "downloadables",
+        0,
+        &BitWriter::default(),
+        false,
+    );
+
+    let entries = user_info_entries(&[(
+        None,
+        Some("0"),
+        player_info("scout main", 7, "[U:1:1234]", 1234),
+    )]);
+    let snap = snappy(&entries.bytes);
+    let mut compressed = BitWriter::default();
+    compressed.bytes(&(entries.bytes.len() as u32).to_le_bytes());
+    compressed.bytes(&(snap.len() as u32).to_le_bytes());
+    compressed.bytes(b"SNAP");
+    compressed.bytes(&snap);
+    create_table(&mut signon, "userinfo", 1, &compressed, true);
+    packet_frame(&mut bytes, 1, 0, &signon);
+
+    let mut packet = BitWriter::default();
+    packet.bits(3, 6);
+    packet.bits(3001, 32);
+    packet.bits(0, 32);
+
+    // A reliable Sounds message has no count byte.
+    packet.bits(17, 6);
+    packet.bit(true);
+    packet.bits(12, 8);
+    packet.bits(0xabc, 12);
+
+    let entries = user_info_entries(&[
+        (
+            Some(0),
+            None,
+            player_info("scout alt", 7, "[U:1:1234]", 1234),
+        ),
+        (
+            None,
+            Some("1"),
+            player_info("late sniper", 9, "[U:1:99]", 99),
+        ),
+    ]);
+    packet.bits(13, 6);
+    packet.bits(1, 5);
+    packet.bit(true);
+    packet.bits(2, 16);
+    packet.bits(entries.len as u32, 20);
+    packet.append(&entries);
+    packet_frame(&mut bytes, 2, 2000, &packet);
+
+    bytes.push(7);
+    bytes.extend(3960i32.to_le_bytes());
+    bytes
+}
+
+/// A demo whose first packet at demo tick 1 was sent at `server_tick`.
+pub(super) fn demo_bytes(server_tick: u32) -> Vec<u8> {
+    let mut bytes = b"HL2DEMO\0".to_vec();
+    bytes.extend(3i32.to_le_bytes());
+    bytes.extend(24i32.to_le_bytes());
+    for text in ["SourceTV", "SourceTV Demo", "cp_process_final", "tf"] {
+        let mut field = vec![0; 260];
+        field[..text.len()].copy_from_slice(text.as_bytes());
+        bytes.extend(field);
+    }
+    bytes.extend(60.0f32.to_le_bytes());
+    bytes.extend(3960i32.to_le_bytes());
+    bytes.extend(3900i32.to_le_bytes());
+    bytes.extend(0i32.to_le_bytes());
+
+    bytes.push(3);
+    bytes.extend(0i32.to_le_bytes());
+
+    let tables = string_tables(&[
+        player_info("SourceTV", 2, "BOT", 0),
+        player_info("scout main", 7, "[U:1:1234]", 1234),
+    ]);
+    bytes.push(8);
+    bytes.extend(10i32.to_le_bytes());
+    bytes.extend((tables.len() as i32).to_le_bytes());
+    bytes.extend(tables);
+
+    let mut packet = BitWriter::default();
+    packet.bits(3, 6);
+    packet.bits(server_tick, 32);
+    packet.bits(0, 32);
+    bytes.push(2);
+    bytes.extend(1i32.to_le_bytes());
+    bytes.extend([0; 84]);
+    bytes.extend((packet.bytes.len() as i32).to_le_bytes());
+    bytes.extend(packet.bytes);
+
+    bytes.push(7);
+    bytes.extend(3960i32.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn steam_id_formats() {
+    assert_eq!(account_id("[U:1:1234]"), Some(1234));
+    assert_eq!(account_id("STEAM_0:0:617"), Some(1234));
+    assert_eq!(account_id("76561197960266962"), Some(1234));
+    assert_eq!(account_id("BOT"), None);
+}
+
+#[test]
+fn reads_header_and_roster() {
+    let demo = Demo::from_bytes(&demo_bytes(1001)).unwrap();
+
+    assert_eq!(demo.header.map_name, "cp_process_final");
+    assert_eq!(demo.header.ticks, 3960);
+    assert_eq!(demo.roster.players.len(), 2);
+    assert_eq!(demo.server_tick_offset, Some(1000));
+
+    let player = demo.roster.find("STEAM_0:0:617", 2000).unwrap();
+    assert_eq!(player.name, "scout main");
+    assert_eq!(player.user_id, 7);
+    assert_eq!(player.steam_id64(), 76561197960266962);
+}
+
+#[test]
+fn spectates_clip_target() {
+    let mut generator = Generator::new();
+    generator.roster = Demo::from_bytes(&demo_bytes(1001)).unwrap().roster;
+
+    let mut bookmark = Bookmark::new(1000, "kill");
+    bookmark.player = Some("[U:1:1234]".to_string());
+
+    let vdm = generator.generate(&[bookmark]);
+
+    let spectate = vdm.nth(1).props();
+    assert_eq!(spectate.name, "Spectate scout main");
+    assert_eq!(spectate.start, TimePoint::Tick(Tick(500)));
+    assert_eq!(spectate.commands, "spec_player #7; spec_mode 4");
+}
+
+#[test]
+fn follows_user_info_updates() {
+    let demo = Demo::from_bytes(&late_join_demo()).unwrap();
+
+    assert_eq!(demo.server_tick_offset, Some(1001));
+    assert!(demo.unread_packets.is_empty());
+    assert_eq!(demo.roster.players.len(), 3);
+    assert_eq!(
+        demo.roster.find("[U:1:1234]", 100).unwrap().name,
+        "scout main"
+    );
+    assert_eq!(
+        demo.roster.find("[U:1:1234]", 2500).unwrap().name,
+        "scout alt"
+    );
+
+    let late = demo.roster.find("[U:1:99]", 2500).unwrap();
+    assert_eq!((late.user_id, late.tick), (9, 2000));
+
+    let mut generator = Generator::new();
+    generator.roster = demo.roster;
+
+    let mut clips = vec![Clip::new("late", 2500, 3000), Clip::new("gone", 3000, 3500)];
+    clips[0].target = Some("[U:1:99]".to_string());
+    clips[1].target = Some("[U:1:555]".to_string());
+
+    let unresolved = generator.unresolved(&clips);
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].name, "gone");
+}
@@ -0,0 +1,35 @@
+use crate::edl::EditList;
+use crate::generator::{Bookmark, Generator};
+
+#[test]
+fn exports_generated_clips() {
+    let mut generator = Generator::new();
+    generator.profile.formats = vec![crate::recording::OutputFormat::H264];
+    let mut vdm = generator.generate(&[Bookmark::new(1000, "kill"), Bookmark::new(9000, "cap")]);
+    vdm.name = "match".to_string();
+
+    let segments = vdm.recorded_segments(66.0);
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[1].output, "clip_2");
+    assert_eq!(
+        (segments[1].start_tick, segments[1].stop_tick),
+        (8500, 9300)
+    );
+
+    let mut list = EditList::from_vdm(&vdm, 60, 66.0);
+    list.media_root = "/home/me/tf/my movies".into();
+    assert_eq!(list.clips[0].frames, 727);
+
+    let edl = list.to_edl();
+    assert!(edl.starts_with("TITLE: match\r\n"));
+    assert!(edl.contains(
+        "002  AX       AA/V  C        00:00:00:00 00:00:12:07 01:00:12:07 01:00:24:14\r\n* FROM CLIP NAME: clip_2.mp4\r\n* COMMENT: Start cap\r\n"
+    ));
+
+    let fcpxml = list.to_fcpxml().unwrap();
+    assert!(fcpxml.contains("src=\"file:///home/me/tf/my%20movies/clip_1.mp4\""));
+    assert!(fcpxml.contains("offset=\"727/60s\" duration=\"727/60s\""));
+
+    list.fps = 0;
+    assert!(list.to_fcpxml().is_err());
+}
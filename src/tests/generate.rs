@@ -1,4 +1,5 @@
 use vdm::action::{Action, ActionType};
+use vdm::time::Tick;
 use vdm::VDM;
 
 fn construct() -> VDM {
@@ -6,13 +7,13 @@ fn construct() -> VDM {
 
     let mut pause = Action::new(ActionType::Pause);
     pause.props_mut().duration = 5.0;
-    pause.props_mut().start_tick = Some(1200);
+    pause.props_mut().start = Tick(1200).into();
     vdm.add(pause);
 
     let mut change_playback_rate = Action::new(ActionType::ChangePlaybackRate);
     change_playback_rate.props_mut().name = String::from("testrate");
-    change_playback_rate.props_mut().start_tick = Some(6400);
-    change_playback_rate.props_mut().stop_tick = Some(12800);
+    change_playback_rate.props_mut().start = Tick(6400).into();
+    change_playback_rate.props_mut().stop = Tick(12800).into();
     change_playback_rate.props_mut().playback_rate = 2.0;
     vdm.add(change_playback_rate);
 
@@ -21,5 +22,5 @@ fn construct() -> VDM {
 
 fn main() {
     let vdm = construct();
-    vdm.export("src/tests/test.vdm");
+    vdm.export("src/tests/test.vdm").unwrap();
 }
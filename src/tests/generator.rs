@@ -0,0 +1,41 @@
+use crate::action::Action;
+use crate::generator::{Bookmark, Generator};
+use crate::time::{Tick, TimePoint};
+
+#[test]
+fn merges_nearby_bookmarks() {
+    let mut generator = Generator::new();
+    generator.merge_distance = 100;
+
+    let clips = generator.clips(&[
+        Bookmark::new(5000, "airshot"),
+        Bookmark::new(1000, "kill"),
+        Bookmark::new(1500, "double"),
+    ]);
+
+    assert_eq!(clips.len(), 2);
+    assert_eq!(clips[0].name, "kill + double");
+    assert_eq!((clips[0].start_tick, clips[0].stop_tick), (500, 1800));
+    assert_eq!((clips[1].start_tick, clips[1].stop_tick), (4500, 5300));
+}
+
+#[test]
+fn skips_dead_time() {
+    let generator = Generator::new();
+    let vdm = generator.generate(&[Bookmark::new(600, "early"), Bookmark::new(5000, "late")]);
+
+    // The first window starts too close to the beginning to be worth skipping.
+    assert_eq!(vdm.len(), 5);
+    assert_eq!(vdm.first().props().name, "Start early");
+
+    let Action::SkipAhead(props) = vdm.nth(2) else {
+        panic!("expected SkipAhead");
+    };
+    assert_eq!(props.name, "Skip to late");
+    assert_eq!(props.start, TimePoint::Tick(Tick(901)));
+    assert_eq!(props.skip_to, TimePoint::Tick(Tick(4499)));
+
+    assert_eq!(vdm.last().props().name, "Stop late");
+    assert!(vdm.last().props().commands.starts_with("endmovie"));
+    assert!(vdm.nth(3).props().commands.contains("startmovie clip_2"));
+}
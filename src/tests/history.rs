@@ -0,0 +1,84 @@
+use crate::action::{Action, ActionType};
+use crate::history::{Change, History};
+use crate::time::{Tick, TimePoint};
+use crate::VDM;
+
+fn named(factory: ActionType, name: &str) -> Action {
+    let mut action = Action::new(factory);
+    action.props_mut().name = name.to_string();
+    action
+}
+
+fn names(history: &History) -> Vec<String> {
+    history
+        .vdm()
+        .actions
+        .iter()
+        .map(|action| action.props().name)
+        .collect()
+}
+
+#[test]
+fn undoes_and_redoes_each_edit() {
+    let mut history = History::new(VDM::new());
+    history.add(named(ActionType::PlayCommands, "a"));
+    history.add(named(ActionType::Pause, "b"));
+    history.add(named(ActionType::SkipAhead, "c"));
+    history.move_action(2, 0);
+    history.remove(1);
+
+    assert_eq!(names(&history), ["c", "b"]);
+
+    assert!(history.undo());
+    assert_eq!(names(&history), ["c", "a", "b"]);
+    assert!(history.undo());
+    assert_eq!(names(&history), ["a", "b", "c"]);
+    assert!(history.redo());
+    assert!(history.redo());
+    assert_eq!(names(&history), ["c", "b"]);
+    assert!(!history.redo());
+}
+
+#[test]
+fn shifts_are_undone_exactly() {
+    let mut action = named(ActionType::PlayCommands, "a");
+    action.props_mut().start = Tick(100).into();
+    let mut history = History::new(VDM::new());
+    history.add(action);
+
+    // Shifting below zero clears the start, so only the recorded props can bring it back.
+    history.shift_by_tick(0, Tick(-200), 66.0);
+    assert_eq!(history.vdm().first().props().start, TimePoint::Unset);
+
+    history.undo();
+    assert_eq!(
+        history.vdm().first().props().start,
+        TimePoint::Tick(Tick(100))
+    );
+}
+
+#[test]
+fn transactions_undo_together() {
+    let mut history = History::new(VDM::new());
+    history.add(named(ActionType::PlayCommands, "a"));
+
+    history.transaction(|history| {
+        history.add(named(ActionType::Pause, "b"));
+        let mut props = history.vdm().first().props();
+        props.commands = "echo hi".to_string();
+        history.set_nth_props(0, props);
+    });
+    history.take_changes();
+
+    history.undo();
+    assert_eq!(names(&history), ["a"]);
+    assert_eq!(history.vdm().first().props().commands, "");
+    assert_eq!(
+        history.take_changes(),
+        [Change::Changed(0), Change::Removed(1)]
+    );
+
+    history.undo();
+    assert!(history.vdm().is_empty());
+    assert!(!history.can_undo());
+}
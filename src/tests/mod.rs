@@ -1,6 +1,31 @@
 use crate::action::{Action, ActionType, Properties};
+use crate::time::{Seconds, Tick, TimePoint};
 use crate::VDM;
 
+mod assets;
+mod batch;
+mod beats;
+mod colour;
+mod demo;
+mod edl;
+mod generator;
+mod history;
+mod optimize;
+mod playback;
+mod project;
+mod ramp;
+mod recording;
+mod segments;
+mod sound;
+mod subtitles;
+mod sync;
+mod syntax;
+mod time;
+mod titles;
+mod transitions;
+mod vpk;
+mod zoom;
+
 #[test]
 fn parse_open() {
     let vdm = VDM::open("src/tests/test.vdm").unwrap();
@@ -23,3 +48,61 @@ fn roundtrip() {
 
     assert_eq!(as_string, back_to_string);
 }
+
+#[test]
+fn ids_survive_edits() {
+    let mut vdm = parse();
+    let id = vdm.last().id();
+
+    vdm.remove_first();
+    vdm.create_action(ActionType::Pause);
+
+    assert_eq!(vdm.position(id), Some(0));
+    assert_eq!(vdm.find(id).unwrap().props().name, "testrate");
+    assert_ne!(vdm.last().duplicate().id(), vdm.last().id());
+
+    let mut props = vdm.first().props();
+    props.name = "renamed".to_string();
+    vdm.set_first_props(props);
+    assert_eq!(vdm.first().id(), id);
+
+    // Props made from scratch don't carry an ID over.
+    vdm.set_first_props(Properties::new());
+    assert_eq!(vdm.find(id).unwrap().props().name, "Unnamed");
+}
+
+#[test]
+fn shifts_both_ends() {
+    let mut action = Action::new(ActionType::ChangePlaybackRate);
+    let props = action.props_mut();
+    props.start = Tick(100).into();
+    props.stop = Tick(200).into();
+
+    props.shift_by_tick(Tick(500), 66.0);
+    assert_eq!(
+        (props.start, props.stop),
+        (TimePoint::Tick(Tick(600)), TimePoint::Tick(Tick(700)))
+    );
+
+    props.shift_by_time(Seconds(-1.0), 100.0);
+    assert_eq!(
+        (props.start, props.stop),
+        (TimePoint::Tick(Tick(500)), TimePoint::Tick(Tick(600)))
+    );
+}
+
+#[test]
+fn keeps_original_keys() {
+    let mut vdm = VDM::from(
+        "demoactions\n{\n\t\"3\"\n\t{\n\t\tfactory \"Pause\"\n\t}\n\t\"7\"\n\t{\n\t\tfactory \"Pause\"\n\t}\n}\n",
+    );
+    vdm.remove_first();
+    vdm.create_action(ActionType::PlayCommands);
+
+    assert!(vdm.to_string().contains("\t\"1\"\r\n"));
+
+    vdm.keep_keys = true;
+    let text = vdm.to_string();
+    assert!(text.contains("\t\"7\"\r\n"));
+    assert!(text.contains("\t\"8\"\r\n"));
+}
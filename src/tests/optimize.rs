@@ -0,0 +1,68 @@
+use crate::action::ActionType;
+use crate::time::{Tick, TimePoint};
+use crate::VDM;
+
+fn skip(vdm: &mut VDM, start: i64, to: i64) {
+    let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+    props.start = Tick(start).into();
+    props.skip_to = Tick(to).into();
+}
+
+#[test]
+fn removes_no_ops_and_merges_skips() {
+    let mut vdm = VDM::new();
+    skip(&mut vdm, 100, 1000);
+    skip(&mut vdm, 500, 2000);
+    skip(&mut vdm, 2500, 2200);
+    vdm.create_action(ActionType::PlayCommands)
+        .props_mut()
+        .start = Tick(2100).into();
+
+    let props = vdm
+        .create_action(ActionType::ChangePlaybackRate)
+        .props_mut();
+    props.start = Tick(2300).into();
+    props.playback_rate = 1.0;
+
+    let commands = vdm.create_action(ActionType::PlayCommands).props_mut();
+    commands.start = Tick(2400).into();
+    commands.commands = "echo kept".to_string();
+
+    let first = vdm.first().id();
+    let second = vdm.nth(1).id();
+
+    let report = vdm.optimize(66.0);
+
+    assert_eq!(report.len(), 4);
+    let merge = report.iter().find(|o| o.id == second).unwrap();
+    assert_eq!(merge.merged_into, Some(first));
+
+    assert_eq!(vdm.len(), 2);
+    assert_eq!(vdm.first().props().skip_to, TimePoint::Tick(Tick(2000)));
+    assert_eq!(vdm.last().props().commands, "echo kept");
+}
+
+#[test]
+fn keeps_actions_playback_depends_on() {
+    let mut vdm = VDM::new();
+
+    // Resets the slow motion below once its stop tick is reached.
+    let props = vdm
+        .create_action(ActionType::ChangePlaybackRate)
+        .props_mut();
+    props.start = Tick(100).into();
+    props.stop = Tick(400).into();
+    props.playback_rate = 1.0;
+
+    let props = vdm
+        .create_action(ActionType::ChangePlaybackRate)
+        .props_mut();
+    props.start = Tick(200).into();
+    props.playback_rate = 0.5;
+
+    skip(&mut vdm, 500, 1000);
+    skip(&mut vdm, 1500, 2000);
+
+    assert!(vdm.optimize(66.0).is_empty());
+    assert_eq!(vdm.len(), 4);
+}
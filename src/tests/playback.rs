@@ -0,0 +1,44 @@
+use crate::action::{Action, ActionType};
+use crate::playback::{Playback, Segment};
+use crate::time::Tick;
+use crate::VDM;
+
+#[test]
+fn simulates_rates_pauses_and_skips() {
+    let mut vdm = VDM::new();
+
+    let props = vdm
+        .create_action(ActionType::ChangePlaybackRate)
+        .props_mut();
+    props.start = Tick(66).into();
+    props.stop = Tick(198).into();
+    props.playback_rate = 0.5;
+
+    let props = vdm.create_action(ActionType::Pause).props_mut();
+    props.start = Tick(330).into();
+    props.duration = 2.0;
+
+    let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+    props.start = Tick(396).into();
+    props.skip_to = Tick(6600).into();
+
+    let props = vdm.create_action(ActionType::StopPlayback).props_mut();
+    props.start = Tick(6666).into();
+
+    let playback = Playback::new(&vdm, 66.0);
+
+    assert_eq!(playback.time_at(66), Some(1.0));
+    assert_eq!(playback.time_at(198), Some(5.0));
+    assert_eq!(playback.time_at(330), Some(7.0));
+    assert_eq!(playback.time_at(396), Some(10.0));
+    assert_eq!(playback.time_at(1000), None);
+    assert_eq!(playback.duration(6600, 6666), Some(1.0));
+    assert_eq!(playback.time_at(7000), None);
+    assert!(playback.stopped);
+
+    assert!(matches!(
+        playback.segments[3],
+        Segment::Pause { tick: 330, duration, .. } if duration == 2.0
+    ));
+    assert_eq!(playback.fired_at(3).unwrap().time, 11.0);
+}
@@ -0,0 +1,86 @@
+use crate::generator::Clip;
+use crate::project::{Project, ProjectDemo};
+use std::fs;
+
+const PROJECT: &str = r#"
+name = "Frag movie"
+
+[profile]
+fps = 120
+name_pattern = "frag_{n}"
+
+[[demos]]
+path = "demos/match1.dem"
+notes = "Pick the airshot"
+clips = [{ name = "airshot", start_tick = 4200, stop_tick = 5000 }]
+
+[[demos]]
+path = "demos/match2.dem"
+clips = [
+    { name = "ubercharge", start_tick = 9000, stop_tick = 9500 },
+    { name = "triple", start_tick = 100, stop_tick = 400 },
+    { name = "double", start_tick = 350, stop_tick = 600 },
+]
+"#;
+
+#[test]
+fn toml_and_json_roundtrip() {
+    let project = Project::from_toml(PROJECT).unwrap();
+    assert_eq!(project.demos.len(), 2);
+    assert_eq!(project.profile.fps, 120);
+    assert_eq!(project.demos[1].clips[0].stop_tick, 9500);
+
+    let json = Project::from_json(project.to_json().unwrap()).unwrap();
+    let toml = Project::from_toml(project.to_toml().unwrap()).unwrap();
+    for copy in [json, toml] {
+        assert_eq!(copy.demos[0].notes, "Pick the airshot");
+        assert_eq!(copy.demos[1].clips[1].name, "triple");
+        assert_eq!(copy.profile.name_pattern, "frag_{n}");
+    }
+
+    assert!(Project::from_json("{ \"demos\": 3 }").is_err());
+}
+
+#[test]
+fn regenerates_without_losing_edits() {
+    let root = std::env::temp_dir().join(format!("vdm-project-{}", std::process::id()));
+    fs::create_dir_all(root.join("demos")).unwrap();
+
+    let mut project = Project::from_toml(PROJECT).unwrap();
+    project.demos.push(ProjectDemo::new("demos/match3.dem"));
+    project.demos[2].clips.push(Clip::new("ace", 1000, 2000));
+    project.save(root.join("movie.toml")).unwrap();
+
+    let mut project = Project::open(root.join("movie.toml")).unwrap();
+    let report = project.regenerate(false).unwrap();
+    assert!(report.iter().all(|vdm| vdm.written && !vdm.hand_edited));
+
+    // Numbering carries on from the demos before, counting overlapping clips once.
+    let second = fs::read_to_string(root.join("demos/match2.vdm")).unwrap();
+    assert!(second.contains("startmovie frag_2") && second.contains("startmovie frag_3"));
+    assert!(second.contains("Start triple + double"));
+    let third = fs::read_to_string(root.join("demos/match3.vdm")).unwrap();
+    assert!(third.contains("startmovie frag_4"));
+    assert_eq!(project.demos[1].vdm.name, "match2");
+
+    fs::write(root.join("demos/match3.vdm"), "demoactions\r\n{\r\n}\r\n").unwrap();
+    project.save(root.join("movie.json")).unwrap();
+
+    let mut project = Project::open(root.join("movie.json")).unwrap();
+    assert!(!project.is_hand_edited(0));
+    assert!(project.is_hand_edited(2));
+    assert_eq!(project.demos[0].vdm.len(), 3);
+
+    let report = project.regenerate(false).unwrap();
+    assert!(report[2].hand_edited && !report[2].written);
+    assert_eq!(
+        fs::read_to_string(root.join("demos/match3.vdm")).unwrap(),
+        "demoactions\r\n{\r\n}\r\n"
+    );
+
+    let report = project.regenerate(true).unwrap();
+    assert!(report[2].written);
+    assert!(!project.is_hand_edited(2));
+
+    fs::remove_dir_all(&root).unwrap();
+}
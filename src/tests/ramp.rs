@@ -0,0 +1,39 @@
+use crate::action::Action;
+use crate::ramp::{Curve, SpeedRamp};
+use crate::time::{Tick, TimePoint};
+
+#[test]
+fn steps_follow_the_curve() {
+    let mut ramp = SpeedRamp::new(Tick(660), Tick(726), 1.0, 0.5);
+    ramp.step = Tick(33);
+
+    let actions = ramp.actions();
+    assert_eq!(actions.len(), 2);
+
+    let rates = actions
+        .iter()
+        .map(|action| action.props().playback_rate)
+        .collect::<Vec<_>>();
+    assert_eq!(rates, [0.875, 0.625]);
+    assert_eq!(actions[1].props().start, TimePoint::Tick(Tick(693)));
+    assert_eq!(actions[1].props().stop, TimePoint::Tick(Tick(726)));
+    assert!(matches!(actions[0], Action::ChangePlaybackRate(_)));
+
+    // 0.5 seconds of demo at 0.875 and at 0.625.
+    let expected = 0.5 / 0.875 + 0.5 / 0.625;
+    assert!((ramp.duration(66.0) - expected).abs() < 1e-9);
+}
+
+#[test]
+fn keyframes_skip_repeated_rates() {
+    let mut ramp = SpeedRamp::new(Tick(0), Tick(100), 1.0, 1.0);
+    ramp.step = Tick(10);
+    ramp.curve = Curve::Keyframes(vec![(0.0, 0.5), (0.5, 0.5), (1.0, 1.0)]);
+    ramp.reset = false;
+
+    let actions = ramp.actions();
+    assert_eq!(actions[0].props().playback_rate, 0.5);
+    assert_eq!(actions[1].props().start, TimePoint::Tick(Tick(50)));
+    assert_eq!(actions.len(), 6);
+    assert!(!actions.last().unwrap().props().stop.is_set());
+}
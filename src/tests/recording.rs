@@ -0,0 +1,50 @@
+use crate::action::Action;
+use crate::recording::{OutputFormat, RecordingProfile};
+use crate::time::{Tick, TimePoint};
+use crate::VDM;
+
+#[test]
+fn profile_from_toml() {
+    let profile = RecordingProfile::from_toml(
+        r#"
+        fps = 120
+        formats = ["h264"]
+        name_pattern = "frag_{n}"
+        pre_commands = ["cl_drawhud 0"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(profile.fps, 120);
+    assert_eq!(profile.formats, vec![OutputFormat::H264]);
+    assert!(profile.post_commands.is_empty());
+    assert_eq!(
+        profile.start_commands(3),
+        "cl_drawhud 0; host_framerate 120; startmovie frag_3 h264"
+    );
+}
+
+#[test]
+fn record_numbers_sequentially() {
+    let profile = RecordingProfile::new();
+    let mut vdm = VDM::new();
+
+    assert_eq!(profile.record(&mut vdm, 100, 500), "clip_1");
+    assert_eq!(profile.record(&mut vdm, 900, 1200), "clip_2");
+    assert_eq!(vdm.len(), 4);
+
+    let Action::PlayCommands(props) = vdm.nth(2) else {
+        panic!("expected PlayCommands");
+    };
+    assert_eq!(props.start, TimePoint::Tick(Tick(900)));
+    assert_eq!(
+        props.commands,
+        "host_framerate 60; startmovie clip_2 tga wav"
+    );
+
+    let Action::PlayCommands(props) = vdm.last() else {
+        panic!("expected PlayCommands");
+    };
+    assert_eq!(props.start, TimePoint::Tick(Tick(1200)));
+    assert_eq!(props.commands, "endmovie; host_framerate 0");
+}
@@ -0,0 +1,56 @@
+use crate::action::ActionType;
+use crate::segments::RecordingKind;
+use crate::time::Tick;
+use crate::VDM;
+
+fn commands(vdm: &mut VDM, tick: i64, commands: &str) {
+    let props = vdm.create_action(ActionType::PlayCommands).props_mut();
+    props.name = format!("At {tick}");
+    props.start = Tick(tick).into();
+    props.commands = commands.to_string();
+}
+
+#[test]
+fn finds_every_kind_of_recording() {
+    let mut vdm = VDM::new();
+    commands(&mut vdm, 100, "startmovie \"intro\" h264; record pov");
+
+    let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+    props.start = Tick(200).into();
+    props.skip_to = Tick(1000).into();
+
+    let props = vdm.create_action(ActionType::Pause).props_mut();
+    props.start = Tick(1100).into();
+    props.duration = 2.0;
+
+    commands(&mut vdm, 1200, "endmovie");
+    commands(&mut vdm, 1300, "startrecording");
+    commands(&mut vdm, 1400, "stoprecording; endmovie");
+    vdm.create_action(ActionType::StopPlayback)
+        .props_mut()
+        .start = Tick(1500).into();
+
+    let segments = vdm.recorded_segments(100.0);
+    assert_eq!(segments.len(), 3);
+
+    let movie = &segments[0];
+    assert_eq!(movie.kind, RecordingKind::Movie);
+    assert_eq!(movie.file_name(), "intro.mp4");
+    assert_eq!((movie.start_tick, movie.stop_tick), (100, 1200));
+    assert_eq!(movie.ranges, [(100, 200), (1000, 1200)]);
+    assert_eq!(movie.ticks(), 300);
+    // The pause is recorded, the skip isn't.
+    assert!((movie.duration - 5.0).abs() < 1e-9);
+
+    // Still going when playback stops.
+    let demo = &segments[1];
+    assert_eq!(demo.kind, RecordingKind::Demo);
+    assert_eq!(demo.file_name(), "pov.dem");
+    assert_eq!(demo.stop_tick, 1500);
+    assert_eq!(demo.ranges, [(100, 200), (1000, 1500)]);
+
+    let recording = &segments[2];
+    assert_eq!(recording.kind, RecordingKind::Recording);
+    assert_eq!(recording.output, "");
+    assert_eq!(recording.ranges, [(1300, 1400)]);
+}
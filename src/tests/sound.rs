@@ -0,0 +1,133 @@
+use crate::action::{Action, ActionType};
+use crate::assets::Assets;
+use crate::playback::Playback;
+use crate::sound;
+use crate::time::{Seconds, Tick, TimePoint};
+use crate::vpk::Vpk;
+use crate::VDM;
+use std::path::Path;
+
+fn wav(format_tag: u16, byte_rate: u32, fact: Option<u32>, data: usize) -> Vec<u8> {
+    let mut chunks = vec![];
+    chunks.extend(b"fmt ");
+    chunks.extend(16u32.to_le_bytes());
+    chunks.extend(format_tag.to_le_bytes());
+    chunks.extend(1u16.to_le_bytes());
+    chunks.extend(22050u32.to_le_bytes());
+    chunks.extend(byte_rate.to_le_bytes());
+    chunks.extend([1, 0, 8, 0]);
+    if let Some(samples) = fact {
+        chunks.extend(b"fact");
+        chunks.extend(4u32.to_le_bytes());
+        chunks.extend(samples.to_le_bytes());
+    }
+    chunks.extend(b"data");
+    chunks.extend((data as u32).to_le_bytes());
+    chunks.extend(vec![0; data]);
+
+    let mut file = b"RIFF".to_vec();
+    file.extend((chunks.len() as u32 + 4).to_le_bytes());
+    file.extend(b"WAVE");
+    file.extend(chunks);
+    file
+}
+
+#[test]
+fn reads_wav_lengths() {
+    assert_eq!(
+        sound::duration(&wav(1, 22050, None, 11025)),
+        Ok(Seconds(0.5))
+    );
+    // ADPCM, where the byte rate is only roughly right.
+    assert_eq!(
+        sound::duration(&wav(2, 11100, Some(44100), 22222)),
+        Ok(Seconds(2.0))
+    );
+    assert!(sound::duration(b"RIFF\0\0\0\0WAVEdata\0\0\0\0").is_err());
+
+    let vpk = Vpk::open("src/tests/fixtures/sounds_dir.vpk").unwrap();
+    assert_eq!(sound::in_vpk(&vpk, "sound/ui/beep.wav"), Ok(Seconds(0.01)));
+}
+
+#[test]
+fn reads_mp3_lengths() {
+    // An ID3 tag, then ten 128kbps 44.1kHz MPEG-1 layer III frames and an ID3v1 tag.
+    let mut mp3 = b"ID3\x04\0\0\0\0\0\x05tagsx".to_vec();
+    for _ in 0..10 {
+        let mut frame = vec![0; 417];
+        frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+        mp3.extend(frame);
+    }
+    mp3.extend(b"TAG");
+    mp3.extend([0; 125]);
+
+    let length = sound::duration(&mp3).unwrap();
+    assert!((length.0 - 11520.0 / 44100.0).abs() < 1e-9);
+    assert_eq!(sound::duration(b"no frames here"), Err("Not an mp3 file"));
+    assert!(sound::open("src/tests/test.vdm").is_err());
+}
+
+#[test]
+fn flags_overlapping_and_cut_off_sounds() {
+    let mut assets = Assets::new();
+    assets
+        .add_vpk(Path::new("src/tests/fixtures/sounds_dir.vpk"))
+        .unwrap();
+    assert_eq!(assets.sound_duration(")ui/beep.wav"), Ok(Seconds(0.01)));
+
+    let mut vdm = VDM::new();
+    for (tick, action_type) in [
+        (0, ActionType::PlaySoundStart),
+        (2, ActionType::PlaySoundStart),
+        (4, ActionType::StopPlayback),
+    ] {
+        let props = vdm.create_action(action_type).props_mut();
+        props.start = Tick(tick).into();
+        props.sound = "ui/beep.wav".to_string();
+    }
+
+    // At 1000 ticks a second, the first beep runs to tick 10 and the second to 12.
+    let issues = vdm.check_sounds(&assets, 1000.0);
+    assert_eq!(
+        issues
+            .iter()
+            .map(|issue| (issue.index, issue.message.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (1, "Starts 0.01s before \"ui/beep.wav\" has finished"),
+            (
+                2,
+                "Stops playback 0.01s before \"ui/beep.wav\" has finished"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn places_music_back_to_back() {
+    let mut vdm = VDM::new();
+    let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+    props.start = Tick(100).into();
+    props.skip_to = Tick(1000).into();
+
+    let playback = Playback::new(&vdm, 100.0);
+    let music = [
+        ("music/a.mp3".to_string(), Seconds(0.5)),
+        ("music/b.mp3".to_string(), Seconds(1.0)),
+        ("music/c.mp3".to_string(), Seconds(1.0)),
+    ];
+    let starts = sound::back_to_back(&music, Tick(0), &playback)
+        .iter()
+        .map(|action: &Action| action.props().start)
+        .collect::<Vec<_>>();
+
+    // The skip at one second jumps the second track's end ahead.
+    assert_eq!(
+        starts,
+        [
+            TimePoint::Tick(Tick(0)),
+            TimePoint::Tick(Tick(50)),
+            TimePoint::Tick(Tick(1050))
+        ]
+    );
+}
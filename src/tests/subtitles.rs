@@ -0,0 +1,45 @@
+use crate::subtitles::{self, Cue, TextStyle};
+use crate::time::{Tick, TimePoint};
+use crate::VDM;
+
+#[test]
+fn parse_srt_and_vtt() {
+    let srt = "1\r\n00:00:01,500 --> 00:00:04,000\r\n<i>First</i> line\r\nSecond line\r\n\r\n2\r\n00:01:00,000 --> 00:01:02,250\r\n\"Quoted\"\r\n";
+    let vtt = "WEBVTT\n\nNOTE ignored\n\nintro\n00:01.500 --> 00:04.000 align:center\nFirst line\n";
+
+    let cues = subtitles::parse(srt);
+    assert_eq!(cues.len(), 2);
+    assert_eq!(
+        cues[0],
+        Cue {
+            start: 1.5,
+            end: 4.0,
+            text: "First line\nSecond line".to_string()
+        }
+    );
+    assert_eq!(cues[1].end, 62.25);
+
+    let cues = subtitles::parse(vtt);
+    assert_eq!(cues.len(), 1);
+    assert_eq!((cues[0].start, cues[0].end), (1.5, 4.0));
+}
+
+#[test]
+fn srt_roundtrip_through_vdm() {
+    let cues = subtitles::parse("1\n00:00:10,000 --> 00:00:13,000\n\"GG\"\nwp\n");
+
+    let mut vdm = VDM::new();
+    for action in subtitles::to_actions(&cues, &TextStyle::new(), 66.0) {
+        vdm.add(action);
+    }
+
+    let props = vdm.first().props();
+    assert_eq!(props.start, TimePoint::Tick(Tick(660)));
+    assert_eq!(props.message, "'GG' wp");
+    assert_eq!(props.hold_time, 2.5);
+
+    assert_eq!(
+        subtitles::to_srt(&subtitles::from_vdm(&vdm, 66.0)),
+        "1\n00:00:10,000 --> 00:00:13,000\n'GG' wp\n\n"
+    );
+}
@@ -0,0 +1,49 @@
+use super::demo::demo_bytes;
+use crate::demo::Demo;
+use crate::generator::{Clip, Generator};
+use crate::sync::{event_offset, DemoSync};
+use crate::time::Tick;
+
+#[test]
+fn maps_ticks_between_demos() {
+    let mut sync = DemoSync::new();
+    let first = sync
+        .add_demo(&Demo::from_bytes(&demo_bytes(1001)).unwrap())
+        .unwrap();
+    let second = sync
+        .add_demo(&Demo::from_bytes(&demo_bytes(2001)).unwrap())
+        .unwrap();
+
+    assert_eq!(sync.map_tick(first, second, 1500), 500);
+    assert_eq!(
+        sync.map_range(first, 500, 1500),
+        vec![Some((500, 1500)), Some((0, 500))]
+    );
+    assert_eq!(
+        sync.map_range(second, 3500, 3900),
+        vec![None, Some((3500, 3900))]
+    );
+}
+
+#[test]
+fn syncs_by_shared_events() {
+    assert_eq!(event_offset(&[100, 200, 300], &[150, 249, 400]), Some(50));
+    assert_eq!(event_offset(&[], &[]), None);
+
+    let mut sync = DemoSync::new();
+    let first = sync
+        .add_demo(&Demo::from_bytes(&demo_bytes(1001)).unwrap())
+        .unwrap();
+    let second = sync
+        .add_by_events("pov", first, &[1000, 2000], &[1200, 2200])
+        .unwrap();
+
+    let vdms = sync.vdms(first, &[Clip::new("kill", 1000, 1500)], &Generator::new());
+
+    assert_eq!(vdms.len(), 2);
+    assert_eq!(vdms[second].name, "pov");
+    assert!(vdms[second]
+        .actions
+        .iter()
+        .any(|action| action.props().start == Tick(1200).into()));
+}
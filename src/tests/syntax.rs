@@ -0,0 +1,72 @@
+use crate::action::ActionType;
+use crate::syntax::{self, Span};
+use crate::validate::Severity;
+use crate::VDM;
+
+const TEXT: &str = "demoactions\n{\n\t\"1\"\n\t{\n\t\tfactory \"SkipAhead\"\n\t\tname \"skip\"\n\t\tstarttick \"200\"\n\t\tskiptotick \"100\"\n\t}\n\t\"5\"\n\t{\n\t\tfactory \"PlayCommands\"\n\t\tcommands \"\"\n\t\tplaybackrate \"2\"\n\t\tstarttick \"abc\"\n\t}\n}\n";
+
+#[test]
+fn spans_match_source() {
+    let document = syntax::parse(TEXT);
+
+    assert!(document.errors.is_empty());
+    assert_eq!(document.blocks.len(), 2);
+
+    let block = &document.blocks[1];
+    assert_eq!(block.key, "5");
+    assert_eq!(&TEXT[block.key_span.start..block.key_span.end], "\"5\"");
+    assert_eq!(block.action_type(), Some(ActionType::PlayCommands));
+
+    let entry = block.entry("starttick").unwrap();
+    assert_eq!(
+        &TEXT[entry.value_span.start..entry.value_span.end],
+        "\"abc\""
+    );
+
+    // Newline only files parse too.
+    assert_eq!(VDM::from(TEXT).len(), 1);
+    assert_eq!(document.try_to_vdm().unwrap_err(), "Invalid number");
+}
+
+#[test]
+fn diagnostics_point_at_problems() {
+    let document = syntax::parse(TEXT);
+    let diagnostics = document.diagnostics(66.0);
+
+    let messages = diagnostics
+        .iter()
+        .map(|d| (d.severity, d.message.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                Severity::Warning,
+                "playbackrate is not used by PlayCommands"
+            ),
+            (Severity::Error, "Invalid number"),
+            (Severity::Warning, "Skips backwards"),
+        ]
+    );
+
+    let span = diagnostics[2].span;
+    assert_eq!(span, document.blocks[0].key_span);
+
+    let errors = syntax::parse("demoactions\n{\n\t\"1\"\n\t{\n\t\tfactory \"Pause\n").errors;
+    assert_eq!(errors[0].message, "Unterminated string");
+    assert_eq!(errors.last().unwrap().span, Span { start: 15, end: 18 });
+}
+
+#[test]
+fn shadowed_values_are_ignored() {
+    let text = "demoactions\n{\n\t\"1\"\n\t{\n\t\tfactory \"Pause\"\n\t\tstarttick \"10\"\n\t\tstarttime \"abc\"\n\t\tduration \"1\"\n\t}\n\t\"2\"\n\t{\n\t\tfactory \"SkipAhead\"\n\t\tstarttick \"200\"\n\t\tskiptotick \"100\"\n\t}\n}\n";
+    let document = syntax::parse(text);
+    let diagnostics = document.diagnostics(66.0);
+
+    assert_eq!(document.to_vdm().len(), 2);
+    assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+
+    let skip = diagnostics.last().unwrap();
+    assert_eq!(skip.message, "Skips backwards");
+    assert_eq!(skip.span, document.blocks[1].key_span);
+}
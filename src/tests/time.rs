@@ -0,0 +1,87 @@
+use crate::action::ActionType;
+use crate::syntax;
+use crate::time::{Seconds, Tick, TimePoint, TimeUnit};
+use crate::VDM;
+use std::cmp::Ordering;
+
+#[test]
+fn shifts_keep_their_unit() {
+    let point = TimePoint::Time(Seconds(2.0));
+
+    assert_eq!(
+        point.shift_ticks(Tick(66), 66.0),
+        TimePoint::Time(Seconds(3.0))
+    );
+    assert_eq!(
+        TimePoint::Tick(Tick(100)).shift_seconds(Seconds(-1.0), 66.0),
+        TimePoint::Tick(Tick(34))
+    );
+    assert_eq!(point.shift_ticks(Tick(-200), 66.0), TimePoint::Unset);
+    assert_eq!(
+        point.compare(&TimePoint::Tick(Tick(132)), 66.0),
+        Some(Ordering::Equal)
+    );
+    assert_eq!(point.compare(&TimePoint::Unset, 66.0), None);
+}
+
+#[test]
+fn tick_wins_over_time() {
+    let text = "demoactions\n{\n\t\"1\"\n\t{\n\t\tfactory \"Pause\"\n\t\tstarttick \"100\"\n\t\tstarttime \"5\"\n\t\tpausetime \"1\"\n\t}\n}\n";
+
+    let vdm = VDM::from(text);
+    assert_eq!(vdm.first().props().start, TimePoint::Tick(Tick(100)));
+
+    let diagnostics = syntax::parse(text).diagnostics(66.0);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "starttime is ignored as the tick is also set"
+    );
+}
+
+#[test]
+fn validates_at_the_demo_tick_rate() {
+    let mut vdm = VDM::new();
+    let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+    props.start = Tick(100).into();
+    props.skip_to = Seconds(1.0).into();
+
+    // One second is past tick 100 on a 128 tick demo, but not on a 66 tick one.
+    assert_eq!(vdm.validate(66.0)[0].message, "Skips backwards");
+    assert!(vdm.validate(128.0).is_empty());
+}
+
+#[test]
+fn normalizes_to_one_unit() {
+    let mut vdm = VDM::new();
+
+    let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+    props.start = Seconds(1.0).into();
+    props.skip_to = Tick(330).into();
+
+    let props = vdm.create_action(ActionType::Pause).props_mut();
+    props.start = Seconds(0.51).into();
+
+    let props = vdm.create_action(ActionType::SkipAhead).props_mut();
+    props.start = Tick(400).into();
+    props.skip_to = Tick(401).into();
+
+    let mut in_time = vdm.clone();
+    let roundings = in_time.normalize_timing(TimeUnit::Time, 66.0);
+    assert!(roundings.is_empty());
+
+    // Written out and read back, times still land on the same ticks.
+    let reread = VDM::from(in_time.to_string());
+    assert_eq!(reread.nth(2).props().skip_to.ticks(66.0), Some(Tick(401)));
+    assert_eq!(
+        reread.nth(2).props().skip_to,
+        in_time.nth(2).props().skip_to
+    );
+
+    let roundings = vdm.normalize_timing(TimeUnit::Tick, 66.0);
+    assert_eq!(vdm.first().props().start, TimePoint::Tick(Tick(66)));
+    assert_eq!(vdm.first().props().skip_to, TimePoint::Tick(Tick(330)));
+    assert_eq!(roundings.len(), 1);
+    assert_eq!(roundings[0].index, 1);
+    assert_eq!(roundings[0].after, TimePoint::Tick(Tick(34)));
+}
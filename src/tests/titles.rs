@@ -0,0 +1,60 @@
+use crate::action::TextEffect;
+use crate::time::{Seconds, Tick, TimePoint};
+use crate::titles::{Placement, TitleCard, TitleSequence};
+
+#[test]
+fn placement_stacks_lines() {
+    assert_eq!(Placement::Center.positions(1, 0.05), [[-1.0, -1.0]]);
+
+    let lines = Placement::Center.positions(3, 0.1);
+    assert_eq!(lines[0][0], -1.0);
+    assert!((lines[0][1] - 0.4).abs() < 1e-9 && (lines[2][1] - 0.6).abs() < 1e-9);
+
+    let lines = Placement::Bottom.positions(2, 0.1);
+    assert!((lines[0][1] - 0.8).abs() < 1e-9 && (lines[1][1] - 0.9).abs() < 1e-9);
+}
+
+#[test]
+fn lines_leave_together() {
+    let card = TitleCard::lower_third("Player", "3 kills");
+    let actions = card.actions("Name", Tick(100), 66.0);
+
+    assert_eq!(actions.len(), 2);
+    for action in &actions {
+        let props = action.props();
+        assert_eq!(props.start, TimePoint::Tick(Tick(100)));
+        assert_eq!(props.fade_in + props.hold_time + props.fade_out, 4.0);
+    }
+    assert_eq!(actions[1].props().name, "Name line 2");
+    assert_eq!(actions[1].props().xy, [0.05, 0.75]);
+}
+
+#[test]
+fn write_out_times_by_length() {
+    let mut card = TitleCard::new("abcd\nef", Placement::Top);
+    card.style.effect = TextEffect::WriteOut;
+    card.char_time = Seconds(0.5);
+    card.duration = Seconds(1.0);
+
+    // Four characters, then two more, then the fade out.
+    assert_eq!(card.length(), Seconds(3.25));
+
+    let actions = card.actions("Card", Tick(0), 10.0);
+    let (first, second) = (actions[0].props(), actions[1].props());
+    assert_eq!(second.start, TimePoint::Tick(Tick(20)));
+    assert_eq!(first.fade_in, 0.5);
+    assert_eq!(first.hold_time, 1.0);
+    assert_eq!(second.hold_time, 0.0);
+}
+
+#[test]
+fn sequence_staggers_cards() {
+    let mut credits = TitleSequence::new(Tick(0));
+    credits.add(TitleCard::new("Recorded by", Placement::Center));
+    credits.add(TitleCard::new("Edited by", Placement::Center));
+    assert_eq!(credits.schedule(66.0), [Tick(0), Tick(264)]);
+
+    credits.stagger = Some(Seconds(1.0));
+    assert_eq!(credits.schedule(66.0), [Tick(0), Tick(66)]);
+    assert_eq!(credits.actions(66.0)[1].props().name, "Title 2");
+}
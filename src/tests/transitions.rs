@@ -0,0 +1,50 @@
+use crate::action::{Action, FadeFlags};
+use crate::colour::Rgba;
+use crate::generator::{Bookmark, Generator};
+use crate::time::{Tick, TimePoint};
+use crate::transitions::{Preset, Transition};
+
+#[test]
+fn presets_set_fade_flags() {
+    let (out, fade_in) = Transition::new(Preset::FadeToBlack).around(Tick(1000), 66.0);
+
+    let props = out.props();
+    assert_eq!(props.start, TimePoint::Tick(Tick(967)));
+    assert_eq!(props.duration, 0.5);
+    assert_eq!(props.rgba1, Rgba::BLACK);
+    assert_eq!(props.fade_flags, FadeFlags::OUT | FadeFlags::STAYOUT);
+
+    let props = fade_in.props();
+    assert_eq!(props.start, TimePoint::Tick(Tick(1000)));
+    assert_eq!(props.fade_flags, FadeFlags::IN | FadeFlags::PURGE);
+
+    let (out, fade_in) =
+        Transition::new(Preset::TintedModulate(Rgba::new(255, 0, 0, 128))).around(Tick(0), 66.0);
+    assert_eq!(out.props().start, TimePoint::Tick(Tick(0)));
+    assert!(out.props().fade_flags.contains(FadeFlags::MODULATE));
+    assert!(fade_in.props().fade_flags.contains(FadeFlags::MODULATE));
+
+    let (_, fade_in) =
+        Transition::new(Preset::DipToColour(Rgba::rgb(0, 0, 255))).around(Tick(66), 66.0);
+    assert_eq!(fade_in.props().start, TimePoint::Tick(Tick(73)));
+}
+
+#[test]
+fn compiler_fades_between_clips() {
+    let mut generator = Generator::new();
+    generator.transition = Some(Transition::new(Preset::FlashWhite));
+
+    let vdm = generator.generate(&[Bookmark::new(600, "early"), Bookmark::new(5000, "late")]);
+    let fades = vdm
+        .actions
+        .iter()
+        .filter(|action| matches!(action, Action::ScreenFadeStart(_)))
+        .map(|action| action.props().start)
+        .collect::<Vec<_>>();
+
+    // Out ahead of the first clip's stop at 900, in at the second clip's start at 4500.
+    assert_eq!(
+        fades,
+        [TimePoint::Tick(Tick(897)), TimePoint::Tick(Tick(4500))]
+    );
+}
@@ -0,0 +1,51 @@
+use crate::vpk::{crc32, Vpk};
+
+#[test]
+fn lists_tree() {
+    let vpk = Vpk::open("src/tests/fixtures/pak01_dir.vpk").unwrap();
+    assert_eq!(vpk.version, 2);
+    assert_eq!(
+        vpk.paths().collect::<Vec<_>>(),
+        [
+            "readme.txt",
+            "resource/clientscheme.res",
+            "sound/ui/beep.wav"
+        ]
+    );
+    assert_eq!(vpk.list(""), ["readme.txt", "resource/", "sound/"]);
+    assert_eq!(vpk.list("sound/ui/"), ["beep.wav"]);
+    assert!(vpk.contains("Sound\\UI\\Beep.wav"));
+    assert!(!vpk.contains("sound/ui"));
+    assert_eq!(vpk.entry("sound/ui/beep.wav").unwrap().size(), 44);
+}
+
+#[test]
+fn reads_entries() {
+    let vpk = Vpk::open("src/tests/fixtures/pak01_dir.vpk").unwrap();
+
+    // Preload only, the dir file's own data and a numbered archive.
+    assert_eq!(
+        vpk.read("resource/clientscheme.res").unwrap(),
+        b"Scheme { Fonts { Default { } } }"
+    );
+    assert_eq!(vpk.read("readme.txt").unwrap(), b"fixture vpk\n");
+
+    let wav = vpk.read("sound/ui/beep.wav").unwrap();
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(wav.len(), 44);
+
+    assert_eq!(vpk.read("missing.txt"), Err("File is not in the vpk"));
+}
+
+#[test]
+fn rejects_bad_data() {
+    let vpk = Vpk::open("src/tests/fixtures/single.vpk").unwrap();
+    assert_eq!(vpk.version, 1);
+    assert_eq!(
+        vpk.read("sound/music/song.wav"),
+        Err("File doesn't match its CRC")
+    );
+
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    assert!(Vpk::open("src/tests/test.vdm").is_err());
+}
@@ -0,0 +1,52 @@
+use crate::time::{Tick, TimePoint};
+use crate::zoom::{Zoom, ZoomSequence};
+use crate::VDM;
+
+#[test]
+fn chains_zooms_without_overlap() {
+    let mut sequence = ZoomSequence::new(90.0, Tick(660));
+    sequence.add(Zoom::new(40.0, 0.5, 2.0, 1.0));
+
+    let mut second = Zoom::new(60.0, 1.0, 0.0, 0.0);
+    second.start = Some(Tick(700));
+    second.zoom_out = None;
+    sequence.add(second);
+
+    // 3.5 seconds at 66 ticks a second.
+    assert_eq!(sequence.schedule(66.0), [Tick(660), Tick(891)]);
+
+    let actions = sequence.actions(66.0);
+    let props = actions[0].props();
+    assert_eq!(props.final_fov, 40.0);
+    assert_eq!(
+        (props.fade_out, props.hold_time, props.fade_in),
+        (0.5, 2.0, 1.0)
+    );
+    assert!(!props.stayout);
+    assert_eq!(actions[1].props().start, TimePoint::Tick(Tick(891)));
+    assert!(actions[1].props().stayout);
+    // Staying zoomed still takes the time to get there.
+    assert_eq!(actions[1].props().fade_out, 1.0);
+
+    let mut vdm = VDM::new();
+    actions.into_iter().for_each(|action| vdm.add(action));
+    let parsed = VDM::from(vdm.to_string());
+    assert!(parsed.first().props().spline);
+    assert!(parsed.last().props().stayout);
+}
+
+#[test]
+fn exports_fov_keyframes() {
+    let mut sequence = ZoomSequence::new(90.0, Tick(0));
+    sequence.add(Zoom::new(40.0, 0.5, 2.0, 1.0));
+
+    let fovs = sequence
+        .keyframes(66.0)
+        .iter()
+        .map(|keyframe| (keyframe.tick.0, keyframe.fov))
+        .collect::<Vec<_>>();
+    assert_eq!(fovs, [(0, 90.0), (33, 40.0), (165, 40.0), (231, 90.0)]);
+
+    let csv = sequence.to_csv(66.0);
+    assert!(csv.starts_with("tick,seconds,fov,interpolation\n0,0.000,90.000,smooth\n"));
+}
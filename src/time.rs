@@ -0,0 +1,184 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Neg, Sub};
+
+/// A demo tick, or a number of ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Tick(pub i64);
+
+/// A time in seconds, or a number of seconds.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Seconds(pub f64);
+
+impl Tick {
+    pub fn to_seconds(self, tick_rate: f64) -> Seconds {
+        Seconds(self.0 as f64 / tick_rate)
+    }
+}
+
+impl Seconds {
+    /// Rounds to the nearest tick.
+    pub fn to_ticks(self, tick_rate: f64) -> Tick {
+        Tick((self.0 * tick_rate).round() as i64)
+    }
+}
+
+impl Add for Tick {
+    type Output = Tick;
+
+    fn add(self, other: Tick) -> Tick {
+        Tick(self.0 + other.0)
+    }
+}
+
+impl Sub for Tick {
+    type Output = Tick;
+
+    fn sub(self, other: Tick) -> Tick {
+        Tick(self.0 - other.0)
+    }
+}
+
+impl Neg for Tick {
+    type Output = Tick;
+
+    fn neg(self) -> Tick {
+        Tick(-self.0)
+    }
+}
+
+impl Add for Seconds {
+    type Output = Seconds;
+
+    fn add(self, other: Seconds) -> Seconds {
+        Seconds(self.0 + other.0)
+    }
+}
+
+impl Sub for Seconds {
+    type Output = Seconds;
+
+    fn sub(self, other: Seconds) -> Seconds {
+        Seconds(self.0 - other.0)
+    }
+}
+
+impl Neg for Seconds {
+    type Output = Seconds;
+
+    fn neg(self) -> Seconds {
+        Seconds(-self.0)
+    }
+}
+
+impl Display for Tick {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Display for Seconds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// The unit a time point is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Tick,
+    Time,
+}
+
+/// When something happens, in ticks or seconds as written in the vdm.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimePoint {
+    #[default]
+    Unset,
+    Tick(Tick),
+    Time(Seconds),
+}
+
+impl From<Tick> for TimePoint {
+    fn from(tick: Tick) -> Self {
+        TimePoint::Tick(tick)
+    }
+}
+
+impl From<Seconds> for TimePoint {
+    fn from(time: Seconds) -> Self {
+        TimePoint::Time(time)
+    }
+}
+
+impl TimePoint {
+    pub fn is_set(&self) -> bool {
+        !matches!(self, TimePoint::Unset)
+    }
+
+    pub fn ticks(&self, tick_rate: f64) -> Option<Tick> {
+        match *self {
+            TimePoint::Unset => None,
+            TimePoint::Tick(tick) => Some(tick),
+            TimePoint::Time(time) => Some(time.to_ticks(tick_rate)),
+        }
+    }
+
+    pub fn seconds(&self, tick_rate: f64) -> Option<Seconds> {
+        match *self {
+            TimePoint::Unset => None,
+            TimePoint::Tick(tick) => Some(tick.to_seconds(tick_rate)),
+            TimePoint::Time(time) => Some(time),
+        }
+    }
+
+    /// Moves the point by `ticks`, keeping the unit it is written in.
+    ///
+    /// Points moved before the start of the demo become unset.
+    pub fn shift_ticks(self, ticks: Tick, tick_rate: f64) -> TimePoint {
+        match self {
+            TimePoint::Unset => TimePoint::Unset,
+            TimePoint::Tick(tick) if (tick + ticks).0 < 0 => TimePoint::Unset,
+            TimePoint::Tick(tick) => TimePoint::Tick(tick + ticks),
+            TimePoint::Time(time) => {
+                TimePoint::Time(time).shift_seconds(ticks.to_seconds(tick_rate), tick_rate)
+            }
+        }
+    }
+
+    /// Moves the point by `seconds`, keeping the unit it is written in.
+    ///
+    /// Points moved before the start of the demo become unset.
+    pub fn shift_seconds(self, seconds: Seconds, tick_rate: f64) -> TimePoint {
+        match self {
+            TimePoint::Unset => TimePoint::Unset,
+            TimePoint::Tick(tick) => {
+                TimePoint::Tick(tick).shift_ticks(seconds.to_ticks(tick_rate), tick_rate)
+            }
+            TimePoint::Time(time) if (time + seconds).0 < 0.0 => TimePoint::Unset,
+            TimePoint::Time(time) => TimePoint::Time(time + seconds),
+        }
+    }
+
+    /// The same point written in `unit`, times are rounded to the nearest tick.
+    pub fn to_unit(&self, unit: TimeUnit, tick_rate: f64) -> TimePoint {
+        match unit {
+            TimeUnit::Tick => self
+                .ticks(tick_rate)
+                .map_or(TimePoint::Unset, TimePoint::Tick),
+            TimeUnit::Time => self
+                .seconds(tick_rate)
+                .map_or(TimePoint::Unset, TimePoint::Time),
+        }
+    }
+
+    /// Orders two points by when they happen, unset points can't be compared.
+    pub fn compare(&self, other: &TimePoint, tick_rate: f64) -> Option<Ordering> {
+        match (self, other) {
+            (TimePoint::Tick(a), TimePoint::Tick(b)) => Some(a.cmp(b)),
+            _ => self
+                .seconds(tick_rate)?
+                .partial_cmp(&other.seconds(tick_rate)?),
+        }
+    }
+}
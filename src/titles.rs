@@ -0,0 +1,216 @@
+use crate::action::{Action, ActionType, TextEffect};
+use crate::subtitles::TextStyle;
+use crate::time::{Seconds, Tick};
+
+/// Where a card sits on screen.
+///
+/// The engine takes `x` and `y` as 0 to 1 from the top left corner of the text, with -1
+/// centering the text on that axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placement {
+    Center,
+    Top,
+    Bottom,
+    /// Left aligned, a little below the middle, for names and captions.
+    LowerThird,
+    TopLeft,
+    BottomLeft,
+    /// The first line goes at `(x, y)`, either can be -1.
+    At(f64, f64),
+}
+
+impl Placement {
+    /// The `x` and `y` of every line of an `n` line card.
+    pub fn positions(&self, lines: usize, spacing: f64) -> Vec<[f64; 2]> {
+        // The y the block is placed from, and how far up the block hangs from it.
+        let (x, y, anchor) = match *self {
+            Placement::Center => (-1.0, -1.0, 0.5),
+            Placement::Top => (-1.0, 0.1, 0.0),
+            Placement::Bottom => (-1.0, 0.9, 1.0),
+            Placement::LowerThird => (0.05, 0.7, 0.0),
+            Placement::TopLeft => (0.05, 0.1, 0.0),
+            Placement::BottomLeft => (0.05, 0.9, 1.0),
+            Placement::At(x, y) => (x, y, 0.0),
+        };
+
+        // A single line can be left for the engine to center.
+        if lines <= 1 {
+            return vec![[x, y]];
+        }
+
+        let (y, anchor) = if y < 0.0 { (0.5, 0.5) } else { (y, anchor) };
+        let first = y - (lines - 1) as f64 * spacing * anchor;
+
+        (0..lines)
+            .map(|line| [x, first + line as f64 * spacing])
+            .collect()
+    }
+}
+
+/// A message of one or more lines, shown together.
+///
+/// Messages can't hold line breaks, so each line is its own `TextMessageStart`.
+#[derive(Debug, Clone)]
+pub struct TitleCard {
+    pub lines: Vec<String>,
+    pub placement: Placement,
+    /// How long the card is on screen, from the first line showing to the end of the fade out.
+    pub duration: Seconds,
+    /// Used for everything but the position, which comes from the placement.
+    pub style: TextStyle,
+    pub line_spacing: f64,
+    /// How long `WriteOut` takes to write each character.
+    pub char_time: Seconds,
+}
+
+impl TitleCard {
+    /// Every line of `text` becomes a line of the card.
+    pub fn new(text: &str, placement: Placement) -> Self {
+        TitleCard {
+            lines: text.lines().map(str::to_string).collect(),
+            placement,
+            duration: Seconds(4.0),
+            style: TextStyle::new(),
+            line_spacing: 0.05,
+            char_time: Seconds(0.05),
+        }
+    }
+
+    /// A name with a line of detail under it, in the same style.
+    pub fn lower_third(name: &str, detail: &str) -> Self {
+        TitleCard::new(&format!("{name}\n{detail}"), Placement::LowerThird)
+    }
+
+    /// When each line shows, after the card starts. `WriteOut` writes the lines one after
+    /// another, everything else shows them all at once.
+    fn offsets(&self) -> Vec<Seconds> {
+        let mut written = Seconds(0.0);
+
+        self.lines
+            .iter()
+            .map(|line| match self.style.effect {
+                TextEffect::WriteOut => {
+                    let offset = written;
+                    written = written + self.writing(line);
+                    offset
+                }
+                _ => Seconds(0.0),
+            })
+            .collect()
+    }
+
+    fn writing(&self, line: &str) -> Seconds {
+        Seconds(self.char_time.0 * line.chars().count() as f64)
+    }
+
+    /// How long the card is actually on screen, longer than `duration` if there wasn't
+    /// time to write it out and fade.
+    pub fn length(&self) -> Seconds {
+        let shortest = match (self.style.effect, self.lines.last()) {
+            (TextEffect::WriteOut, Some(last)) => {
+                let offset = self.offsets().last().copied().unwrap_or(Seconds(0.0));
+                offset.0 + self.writing(last).0 + self.style.fade_out
+            }
+            _ => self.style.fade_in + self.style.fade_out,
+        };
+
+        Seconds(self.duration.0.max(shortest))
+    }
+
+    /// One `TextMessageStart` per line, all leaving at the same time.
+    ///
+    /// The engine shows a message for `fadein + holdtime + fadeout`, except `WriteOut`,
+    /// which takes `fadein` per character instead.
+    pub fn actions(&self, name: &str, start: Tick, tick_rate: f64) -> Vec<Action> {
+        let positions = self
+            .placement
+            .positions(self.lines.len(), self.line_spacing);
+        let length = self.length();
+
+        self.lines
+            .iter()
+            .zip(self.offsets())
+            .zip(positions)
+            .enumerate()
+            .map(|(i, ((line, offset), xy))| {
+                let mut action = Action::new(ActionType::TextMessageStart);
+                let props = action.props_mut();
+
+                let (fade_in, showing) = match self.style.effect {
+                    TextEffect::WriteOut => (self.char_time.0, self.writing(line).0),
+                    _ => (self.style.fade_in, self.style.fade_in),
+                };
+
+                props.name = match self.lines.len() {
+                    1 => name.to_string(),
+                    _ => format!("{name} line {}", i + 1),
+                };
+                props.start = (start + offset.to_ticks(tick_rate)).into();
+                // Quotes don't survive a vdm value.
+                props.message = line.replace('"', "'");
+                props.font = self.style.font.clone();
+                props.fade_in = fade_in;
+                props.fade_out = self.style.fade_out;
+                props.hold_time = (length.0 - offset.0 - showing - self.style.fade_out).max(0.0);
+                props.fx_time = self.style.fx_time;
+                props.effect = self.style.effect;
+                props.xy = xy;
+                props.rgba1 = self.style.rgba1;
+                props.rgba2 = self.style.rgba2;
+
+                action
+            })
+            .collect()
+    }
+}
+
+/// Cards shown one after another, like credits.
+#[derive(Debug, Clone)]
+pub struct TitleSequence {
+    pub name: String,
+    pub start: Tick,
+    /// From one card starting to the next, cards overlap if it's shorter than they are.
+    /// `None` starts each card once the one before has gone.
+    pub stagger: Option<Seconds>,
+    pub cards: Vec<TitleCard>,
+}
+
+impl TitleSequence {
+    pub fn new(start: Tick) -> Self {
+        TitleSequence {
+            name: "Title".to_string(),
+            start,
+            stagger: None,
+            cards: vec![],
+        }
+    }
+
+    pub fn add(&mut self, card: TitleCard) {
+        self.cards.push(card);
+    }
+
+    /// The tick each card starts on.
+    pub fn schedule(&self, tick_rate: f64) -> Vec<Tick> {
+        let mut next = self.start;
+
+        self.cards
+            .iter()
+            .map(|card| {
+                let start = next;
+                next = start + self.stagger.unwrap_or(card.length()).to_ticks(tick_rate);
+                start
+            })
+            .collect()
+    }
+
+    pub fn actions(&self, tick_rate: f64) -> Vec<Action> {
+        self.cards
+            .iter()
+            .zip(self.schedule(tick_rate))
+            .enumerate()
+            .flat_map(|(i, (card, start))| {
+                card.actions(&format!("{} {}", self.name, i + 1), start, tick_rate)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,93 @@
+use crate::action::{Action, ActionType, FadeFlags};
+use crate::colour::Rgba;
+use crate::time::{Seconds, Tick};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    FadeToBlack,
+    FlashWhite,
+    /// Fade through a solid colour, its alpha is ignored.
+    DipToColour(Rgba),
+    /// Tint the screen instead of covering it, the alpha sets how strong the tint is.
+    TintedModulate(Rgba),
+}
+
+/// A fade out to a colour and back in again, for hiding a cut.
+///
+/// Lengths are in demo seconds, the demo player times fades itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub preset: Preset,
+    pub fade_out: Seconds,
+    /// How long the colour stays up before fading back in.
+    pub hold: Seconds,
+    pub fade_in: Seconds,
+}
+
+impl Transition {
+    pub fn new(preset: Preset) -> Self {
+        let (fade_out, hold, fade_in) = match preset {
+            Preset::FadeToBlack => (0.5, 0.0, 0.5),
+            Preset::FlashWhite => (0.05, 0.0, 0.4),
+            Preset::DipToColour(_) => (0.3, 0.1, 0.3),
+            Preset::TintedModulate(_) => (0.5, 0.0, 0.5),
+        };
+
+        Transition {
+            preset,
+            fade_out: Seconds(fade_out),
+            hold: Seconds(hold),
+            fade_in: Seconds(fade_in),
+        }
+    }
+
+    fn rgba(&self) -> Rgba {
+        match self.preset {
+            Preset::FadeToBlack => Rgba::BLACK,
+            Preset::FlashWhite => Rgba::WHITE,
+            Preset::DipToColour(colour) => Rgba { a: 255, ..colour },
+            Preset::TintedModulate(colour) => colour,
+        }
+    }
+
+    fn fade(&self, name: &str, start: Tick, duration: Seconds) -> Action {
+        let mut action = Action::new(ActionType::ScreenFadeStart);
+        let props = action.props_mut();
+        props.name = name.to_string();
+        props.start = start.into();
+        props.duration = duration.0;
+        props.rgba1 = self.rgba();
+        props.fade_flags.set(
+            FadeFlags::MODULATE,
+            matches!(self.preset, Preset::TintedModulate(_)),
+        );
+        action
+    }
+
+    /// Fades out so the colour is fully up at `tick`, and stays up until the fade in.
+    pub fn fade_out_action(&self, tick: Tick, tick_rate: f64) -> Action {
+        let start = (tick - self.fade_out.to_ticks(tick_rate)).max(Tick(0));
+        let mut action = self.fade(&format!("Fade out {tick}"), start, self.fade_out);
+        let props = action.props_mut();
+        props.hold_time = self.hold.0;
+        props.fade_flags |= FadeFlags::OUT | FadeFlags::STAYOUT;
+        action
+    }
+
+    /// Holds the colour from `tick`, then fades back in, clearing the fade out.
+    pub fn fade_in_action(&self, tick: Tick, tick_rate: f64) -> Action {
+        let start = tick + self.hold.to_ticks(tick_rate);
+        let mut action = self.fade(&format!("Fade in {tick}"), start, self.fade_in);
+        let props = action.props_mut();
+        props.fade_flags |= FadeFlags::IN | FadeFlags::PURGE;
+        action
+    }
+
+    /// The fade out and fade in around a cut at `tick`.
+    pub fn around(&self, tick: Tick, tick_rate: f64) -> (Action, Action) {
+        (
+            self.fade_out_action(tick, tick_rate),
+            self.fade_in_action(tick, tick_rate),
+        )
+    }
+}
@@ -0,0 +1,86 @@
+use crate::action::Action;
+use crate::VDM;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem with the action at `index`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub index: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl VDM {
+    /// Checks every action for settings the demo player would ignore or misread.
+    ///
+    /// `tick_rate` is used to compare times given in seconds with ticks.
+    pub fn validate(&self, tick_rate: f64) -> Vec<Issue> {
+        let mut issues = vec![];
+
+        for (index, action) in self.actions.iter().enumerate() {
+            let mut issue = |severity, message: &str| {
+                issues.push(Issue {
+                    index,
+                    severity,
+                    message: message.to_string(),
+                })
+            };
+
+            let props = action.props();
+
+            if props
+                .start
+                .seconds(tick_rate)
+                .is_some_and(|start| start.0 < 0.0)
+            {
+                issue(Severity::Error, "Start is negative");
+            }
+
+            if props.stop.compare(&props.start, tick_rate) == Some(Ordering::Less) {
+                issue(Severity::Error, "Stops before it starts");
+            }
+
+            match action {
+                Action::SkipAhead(props) => match props.skip_to.compare(&props.start, tick_rate) {
+                    _ if !props.skip_to.is_set() => {
+                        issue(Severity::Error, "Has no skiptotick or skiptotime")
+                    }
+                    Some(Ordering::Less | Ordering::Equal) => {
+                        issue(Severity::Warning, "Skips backwards")
+                    }
+                    _ => {}
+                },
+                Action::PlayCommands(props) if props.commands.trim().is_empty() => {
+                    issue(Severity::Warning, "Has no commands")
+                }
+                Action::PlayCommands(props) if props.commands.contains('"') => {
+                    issue(Severity::Error, "Commands can't contain quotes")
+                }
+                Action::TextMessageStart(props) if props.message.is_empty() => {
+                    issue(Severity::Warning, "Has no message")
+                }
+                Action::PlaySoundStart(props) if props.sound.is_empty() => {
+                    issue(Severity::Error, "Has no sound")
+                }
+                Action::Pause(props) if props.duration <= 0.0 => {
+                    issue(Severity::Warning, "Pauses for no time")
+                }
+                Action::ChangePlaybackRate(props) if props.playback_rate <= 0.0 => {
+                    issue(Severity::Error, "Playback rate must be above 0")
+                }
+                Action::ZoomFov(props) if props.final_fov <= 0.0 => {
+                    issue(Severity::Error, "Final FOV must be above 0")
+                }
+                _ => {}
+            }
+        }
+
+        issues
+    }
+}
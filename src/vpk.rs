@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const SIGNATURE: u32 = 0x55aa_1234;
+
+/// Entries in this archive are stored in the directory file, after the tree.
+const DIR_ARCHIVE: u16 = 0x7fff;
+
+/// A file listed in a vpk's directory tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// CRC32 of the whole file.
+    pub crc: u32,
+    /// The start of the file, kept in the tree itself.
+    pub preload: Vec<u8>,
+    pub archive: u16,
+    pub offset: u32,
+    /// Bytes in the archive, after the preload.
+    pub length: u32,
+}
+
+impl Entry {
+    pub fn size(&self) -> usize {
+        self.preload.len() + self.length as usize
+    }
+}
+
+/// A version 1 or 2 vpk, opened from its `_dir.vpk` or a single file vpk.
+#[derive(Debug, Clone)]
+pub struct Vpk {
+    pub version: u32,
+    path: PathBuf,
+    /// Where archive `0x7fff` data starts in the directory file.
+    data_start: u64,
+    /// Lowercase paths with `/` separators.
+    pub entries: BTreeMap<String, Entry>,
+}
+
+impl Vpk {
+    pub fn open(file_path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let path = file_path.as_ref();
+
+        if path.extension().unwrap_or_default() != "vpk" {
+            return Err("File is not a .vpk file");
+        }
+
+        let mut file = File::open(path).map_err(|_| "Unable to read vpk")?;
+
+        // Only the header and tree are read, the file data after them can be large.
+        let mut header = vec![];
+        (&mut file)
+            .take(12)
+            .read_to_end(&mut header)
+            .map_err(|_| "Unable to read vpk")?;
+        let mut reader = Reader {
+            data: &header,
+            at: 0,
+        };
+
+        if reader.u32()? != SIGNATURE {
+            return Err("Not a vpk");
+        }
+
+        let version = reader.u32()?;
+        let tree_size = reader.u32()? as usize;
+
+        let header_size = match version {
+            1 => 12,
+            // File data, archive MD5, other MD5 and signature section sizes.
+            2 => 28,
+            _ => return Err("Unsupported vpk version"),
+        };
+
+        let file_size = file.metadata().map_err(|_| "Unable to read vpk")?.len();
+        if header_size + tree_size as u64 > file_size {
+            return Err("Truncated vpk");
+        }
+
+        file.seek(SeekFrom::Start(header_size))
+            .map_err(|_| "Unable to read vpk")?;
+        let mut tree = vec![0; tree_size];
+        file.read_exact(&mut tree).map_err(|_| "Truncated vpk")?;
+        let mut reader = Reader { data: &tree, at: 0 };
+
+        let mut entries = BTreeMap::new();
+
+        loop {
+            let extension = reader.string(tree_size)?;
+            if extension.is_empty() {
+                break;
+            }
+
+            loop {
+                let dir = reader.string(tree_size)?;
+                if dir.is_empty() {
+                    break;
+                }
+
+                loop {
+                    let name = reader.string(tree_size)?;
+                    if name.is_empty() {
+                        break;
+                    }
+
+                    let crc = reader.u32()?;
+                    let preload_size = reader.u16()? as usize;
+                    let archive = reader.u16()?;
+                    let offset = reader.u32()?;
+                    let length = reader.u32()?;
+
+                    if reader.u16()? != 0xffff {
+                        return Err("Corrupt vpk entry");
+                    }
+
+                    let preload = reader.bytes(preload_size)?.to_vec();
+
+                    // A single space stands for no directory or no extension.
+                    let mut file = match dir.trim() {
+                        "" => name,
+                        dir => format!("{dir}/{name}"),
+                    };
+                    if !extension.trim().is_empty() {
+                        file = format!("{file}.{extension}");
+                    }
+
+                    entries.insert(
+                        normalize(&file),
+                        Entry {
+                            crc,
+                            preload,
+                            archive,
+                            offset,
+                            length,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(Vpk {
+            version,
+            path: path.to_path_buf(),
+            data_start: header_size + tree_size as u64,
+            entries,
+        })
+    }
+
+    /// Every file path, sorted.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// The files directly inside `dir`, and the directories below it, ending in `/`.
+    pub fn list(&self, dir: &str) -> Vec<String> {
+        let dir = normalize(dir);
+        let prefix = match dir.trim_end_matches('/') {
+            "" => String::new(),
+            dir => format!("{dir}/"),
+        };
+
+        let mut children: Vec<String> = vec![];
+
+        for path in self.paths() {
+            let Some(rest) = path.strip_prefix(&prefix) else {
+                continue;
+            };
+
+            let child = match rest.split_once('/') {
+                Some((subdir, _)) => format!("{subdir}/"),
+                None => rest.to_string(),
+            };
+
+            if children.last() != Some(&child) {
+                children.push(child);
+            }
+        }
+
+        children
+    }
+
+    /// Whether the vpk has a file, without case and with either slash.
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(&normalize(path))
+    }
+
+    pub fn entry(&self, path: &str) -> Option<&Entry> {
+        self.entries.get(&normalize(path))
+    }
+
+    /// Reads a file out of the vpk, checking it against its CRC.
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, &'static str> {
+        let entry = self.entry(path).ok_or("File is not in the vpk")?;
+        let mut data = entry.preload.clone();
+
+        if entry.length > 0 {
+            let (archive, offset) = match entry.archive {
+                DIR_ARCHIVE => (self.path.clone(), self.data_start + entry.offset as u64),
+                archive => (self.archive_path(archive)?, entry.offset as u64),
+            };
+
+            let mut file = File::open(archive).map_err(|_| "Unable to open vpk archive")?;
+            file.seek(SeekFrom::Start(offset))
+                .map_err(|_| "Unable to read vpk archive")?;
+
+            let mut rest = vec![0; entry.length as usize];
+            file.read_exact(&mut rest)
+                .map_err(|_| "Unable to read vpk archive")?;
+            data.extend(rest);
+        }
+
+        if crc32(&data) != entry.crc {
+            return Err("File doesn't match its CRC");
+        }
+
+        Ok(data)
+    }
+
+    /// `pak01_dir.vpk` keeps archive 3 in `pak01_003.vpk`.
+    fn archive_path(&self, archive: u16) -> Result<PathBuf, &'static str> {
+        let name = self
+            .path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix("_dir.vpk"))
+            .ok_or("Only _dir.vpk files have archives")?;
+
+        Ok(self.path.with_file_name(format!("{name}_{archive:03}.vpk")))
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/")
+        .trim_start_matches('/')
+        .to_lowercase()
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl Reader<'_> {
+    fn bytes(&mut self, length: usize) -> Result<&[u8], &'static str> {
+        let bytes = self
+            .data
+            .get(self.at..self.at + length)
+            .ok_or("Truncated vpk")?;
+        self.at += length;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, &'static str> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    /// A null terminated string, which has to end before `end`.
+    fn string(&mut self, end: usize) -> Result<String, &'static str> {
+        let rest = self.data.get(self.at..end).ok_or("Truncated vpk")?;
+        let length = rest.iter().position(|&b| b == 0).ok_or("Truncated vpk")?;
+        self.at += length + 1;
+        Ok(String::from_utf8_lossy(&rest[..length]).into_owned())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::action::{Action, ActionType};
+use crate::time::{Seconds, Tick};
+use std::fmt::Write;
+
+/// Zooming to `fov`, holding it, then going back.
+///
+/// Times are in demo seconds, so they stretch with the playback rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zoom {
+    pub fov: f64,
+    pub zoom_in: Seconds,
+    pub hold: Seconds,
+    /// How long the return takes, `None` stays zoomed in.
+    pub zoom_out: Option<Seconds>,
+    /// Eases in and out of each move instead of moving at a constant speed.
+    pub smooth: bool,
+    /// When to start, zooms without one start as soon as the one before has finished.
+    pub start: Option<Tick>,
+}
+
+impl Zoom {
+    pub fn new(fov: f64, zoom_in: f64, hold: f64, zoom_out: f64) -> Self {
+        Zoom {
+            fov,
+            zoom_in: Seconds(zoom_in),
+            hold: Seconds(hold),
+            zoom_out: Some(Seconds(zoom_out)),
+            smooth: true,
+            start: None,
+        }
+    }
+
+    /// How long until the zoom has returned, or has reached its FOV if it stays.
+    pub fn length(&self) -> Seconds {
+        match self.zoom_out {
+            Some(zoom_out) => self.zoom_in + self.hold + zoom_out,
+            None => self.zoom_in,
+        }
+    }
+}
+
+/// The FOV at a point in time, moving towards the next keyframe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FovKeyframe {
+    pub tick: Tick,
+    pub fov: f64,
+    /// How the FOV moves from here to the next keyframe.
+    pub smooth: bool,
+}
+
+/// Zooms one after another from a resting FOV.
+#[derive(Debug, Clone)]
+pub struct ZoomSequence {
+    pub name: String,
+    /// The FOV the player sees when not zoomed.
+    pub base_fov: f64,
+    pub start: Tick,
+    pub zooms: Vec<Zoom>,
+}
+
+impl ZoomSequence {
+    pub fn new(base_fov: f64, start: Tick) -> Self {
+        ZoomSequence {
+            name: "Zoom".to_string(),
+            base_fov,
+            start,
+            zooms: vec![],
+        }
+    }
+
+    pub fn add(&mut self, zoom: Zoom) {
+        self.zooms.push(zoom);
+    }
+
+    /// The tick each zoom starts on, zooms that would overlap the one before are pushed back.
+    pub fn schedule(&self, tick_rate: f64) -> Vec<Tick> {
+        let mut free_from = self.start;
+
+        self.zooms
+            .iter()
+            .map(|zoom| {
+                let start = zoom.start.map_or(free_from, |start| start.max(free_from));
+                free_from = start + zoom.length().to_ticks(tick_rate);
+                start
+            })
+            .collect()
+    }
+
+    pub fn actions(&self, tick_rate: f64) -> Vec<Action> {
+        self.zooms
+            .iter()
+            .zip(self.schedule(tick_rate))
+            .enumerate()
+            .map(|(i, (zoom, start))| {
+                let mut action = Action::new(ActionType::ZoomFov);
+                let props = action.props_mut();
+                props.name = format!("{} {}", self.name, i + 1);
+                props.start = start.into();
+                props.final_fov = zoom.fov;
+                // `fovrateout` is the time to reach the final FOV, `fovratein` the time back.
+                props.fade_out = zoom.zoom_in.0;
+                props.hold_time = zoom.hold.0;
+                props.fade_in = zoom.zoom_out.map_or(0.0, |zoom_out| zoom_out.0);
+                props.stayout = zoom.zoom_out.is_none();
+                props.spline = zoom.smooth;
+                action
+            })
+            .collect()
+    }
+
+    /// Every point where the FOV starts or stops moving.
+    pub fn keyframes(&self, tick_rate: f64) -> Vec<FovKeyframe> {
+        let mut keyframes: Vec<FovKeyframe> = vec![];
+        let mut fov = self.base_fov;
+
+        let mut key = |tick: Tick, fov: f64, smooth: bool| {
+            // A zoom starting where the last one ended replaces its keyframe.
+            if keyframes.last().is_some_and(|last| last.tick == tick) {
+                keyframes.pop();
+            }
+            keyframes.push(FovKeyframe { tick, fov, smooth });
+        };
+
+        for (zoom, start) in self.zooms.iter().zip(self.schedule(tick_rate)) {
+            let zoomed = start + zoom.zoom_in.to_ticks(tick_rate);
+
+            key(start, fov, zoom.smooth);
+            key(zoomed, zoom.fov, zoom.smooth);
+
+            match zoom.zoom_out {
+                Some(zoom_out) => {
+                    let held = zoomed + zoom.hold.to_ticks(tick_rate);
+                    key(held, zoom.fov, zoom.smooth);
+                    key(
+                        held + zoom_out.to_ticks(tick_rate),
+                        self.base_fov,
+                        zoom.smooth,
+                    );
+                    fov = self.base_fov;
+                }
+                None => fov = zoom.fov,
+            }
+        }
+
+        keyframes
+    }
+
+    /// The keyframes as `tick,seconds,fov,interpolation` lines for camera tools.
+    pub fn to_csv(&self, tick_rate: f64) -> String {
+        let mut csv = "tick,seconds,fov,interpolation\n".to_string();
+
+        for keyframe in self.keyframes(tick_rate) {
+            let interpolation = if keyframe.smooth { "smooth" } else { "linear" };
+            let _ = writeln!(
+                csv,
+                "{},{:.3},{:.3},{interpolation}",
+                keyframe.tick,
+                keyframe.tick.to_seconds(tick_rate),
+                keyframe.fov
+            );
+        }
+
+        csv
+    }
+}